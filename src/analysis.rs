@@ -0,0 +1,229 @@
+use std::fmt::Write;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    dev::CircuitGates,
+    plonk::{Circuit, ConstraintSystem},
+};
+
+/// A custom gate: its name and the highest degree among its constraints.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct GateInfo {
+    pub(crate) name: String,
+    pub(crate) degree: usize,
+}
+
+/// The shape of a circuit's `ConstraintSystem`, as produced by its `configure`.
+///
+/// halo2 does not expose the configured gates directly, so gate names and
+/// constraints are read from the output of `CircuitGates`, and the column and
+/// lookup counts from the pinned constraint system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct CircuitInfo {
+    pub(crate) gates: Vec<GateInfo>,
+    pub(crate) num_lookups: usize,
+    pub(crate) num_advice_columns: usize,
+    pub(crate) num_fixed_columns: usize,
+    pub(crate) num_instance_columns: usize,
+    pub(crate) num_selectors: usize,
+    pub(crate) degree: usize,
+}
+
+impl CircuitInfo {
+    pub(crate) fn collect<F: FieldExt, C: Circuit<F>>() -> Self {
+        let mut meta = ConstraintSystem::<F>::default();
+        C::configure(&mut meta);
+
+        let pinned = format!("{:?}", meta.pinned());
+        let count = |field: &str| {
+            pinned
+                .split(&format!("{}: ", field))
+                .nth(1)
+                .and_then(|rest| rest.split(|c: char| !c.is_ascii_digit()).next())
+                .and_then(|n| n.parse().ok())
+                .unwrap_or(0)
+        };
+
+        Self {
+            gates: parse_gates(&CircuitGates::collect::<F, C>().to_string()),
+            num_lookups: pinned.matches("input_expressions").count(),
+            num_advice_columns: count("num_advice_columns"),
+            num_fixed_columns: count("num_fixed_columns"),
+            num_instance_columns: count("num_instance_columns"),
+            num_selectors: count("num_selectors"),
+            degree: meta.degree(),
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        let gates = self
+            .gates
+            .iter()
+            .map(|gate| format!(r#"{{"name":"{}","degree":{}}}"#, escape(&gate.name), gate.degree))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let mut json = String::new();
+        write!(json, r#"{{"gates":[{}],"lookups":{},"#, gates, self.num_lookups).unwrap();
+        write!(
+            json,
+            r#""advice_columns":{},"fixed_columns":{},"instance_columns":{},"#,
+            self.num_advice_columns, self.num_fixed_columns, self.num_instance_columns,
+        )
+        .unwrap();
+        write!(json, r#""selectors":{},"degree":{}}}"#, self.num_selectors, self.degree).unwrap();
+        json
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.chars()
+        .flat_map(|c| match c {
+            '"' | '\\' => vec!['\\', c],
+            _ => vec![c],
+        })
+        .collect()
+}
+
+/// Reads the gates out of the `Display` output of `CircuitGates`: a gate name
+/// line ending in `:`, followed by `- expr` for each unnamed constraint or
+/// `- name:` and an indented `expr` for each named one.
+fn parse_gates(listing: &str) -> Vec<GateInfo> {
+    let mut gates: Vec<GateInfo> = vec![];
+
+    for line in listing.lines() {
+        if line.starts_with("Total ") {
+            break;
+        }
+
+        let expression = if let Some(rest) = line.strip_prefix("- ") {
+            if rest.ends_with(':') {
+                continue;
+            }
+            rest
+        } else if let Some(rest) = line.strip_prefix("  ") {
+            rest
+        } else if let Some(name) = line.strip_suffix(':') {
+            gates.push(GateInfo {
+                name: name.to_string(),
+                degree: 0,
+            });
+            continue;
+        } else {
+            continue;
+        };
+
+        if let Some(gate) = gates.last_mut() {
+            gate.degree = gate.degree.max(ExpressionDegree::of(expression));
+        }
+    }
+
+    gates
+}
+
+/// Computes the degree of an expression in the format printed by `CircuitGates`,
+/// e.g. `S0 * (A0@0 + -A1@1) - 0x2 * F0@0`.
+struct ExpressionDegree<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> ExpressionDegree<'a> {
+    fn of(expression: &'a str) -> usize {
+        Self {
+            chars: expression.chars().peekable(),
+        }
+        .sum()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.chars.next_if_eq(&' ').is_some() {}
+    }
+
+    fn sum(&mut self) -> usize {
+        let mut degree = self.product();
+        loop {
+            self.skip_spaces();
+            match self.chars.peek() {
+                Some('+') | Some('-') => {
+                    self.chars.next();
+                    degree = degree.max(self.product());
+                }
+                _ => return degree,
+            }
+        }
+    }
+
+    fn product(&mut self) -> usize {
+        let mut degree = self.factor();
+        loop {
+            self.skip_spaces();
+            if self.chars.next_if_eq(&'*').is_none() {
+                return degree;
+            }
+            degree += self.factor();
+        }
+    }
+
+    fn factor(&mut self) -> usize {
+        self.skip_spaces();
+        match self.chars.peek() {
+            Some('-') => {
+                self.chars.next();
+                self.factor()
+            }
+            Some('(') => {
+                self.chars.next();
+                let degree = self.sum();
+                self.skip_spaces();
+                self.chars.next_if_eq(&')');
+                degree
+            }
+            _ => {
+                let mut atom = String::new();
+                while let Some(c) = self.chars.next_if(|c| !matches!(c, ' ' | '(' | ')')) {
+                    atom.push(c);
+                }
+                // Selectors and column queries have degree one, constants zero.
+                usize::from(atom.starts_with(['S', 'A', 'F', 'I']))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pasta::Fp;
+
+    use super::{CircuitInfo, ExpressionDegree, GateInfo};
+    use crate::fibonacci::fibonacci1::MyCircuit;
+
+    #[test]
+    fn fibonacci_analysis() {
+        let info = CircuitInfo::collect::<Fp, MyCircuit<Fp>>();
+
+        assert_eq!(
+            info.gates,
+            vec![GateInfo {
+                name: "add".to_string(),
+                degree: 2,
+            }]
+        );
+        assert_eq!(info.num_advice_columns, 3);
+        assert_eq!(info.num_instance_columns, 1);
+        assert_eq!(info.num_lookups, 0);
+
+        assert_eq!(
+            info.to_json(),
+            r#"{"gates":[{"name":"add","degree":2}],"lookups":0,"advice_columns":3,"fixed_columns":0,"#
+                .to_string()
+                + r#""instance_columns":1,"selectors":1,"degree":3}"#
+        );
+    }
+
+    #[test]
+    fn expression_degree() {
+        assert_eq!(ExpressionDegree::of("S0 * (A0@0 + A1@0 - A2@0)"), 2);
+        assert_eq!(ExpressionDegree::of("S1 * A0@0 * (0x1 - A0@0)"), 3);
+        assert_eq!(ExpressionDegree::of("-(A0@0 * A1@1) + 0x2 * F0@0"), 2);
+    }
+}
@@ -0,0 +1,178 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    plonk::{Advice, Column, ConstraintSystem, Expression, Fixed, Instance, VirtualCells},
+};
+
+use crate::{
+    is_zero::is_zero_gadget::{IsZeroChip, IsZeroConfig},
+    numeric::numeric::{FieldChip, FieldConfig},
+    range_check::example1::RangeCheckConfig,
+};
+
+/// Allocates columns for several chips configured into one circuit.
+///
+/// Chips registered through the builder share a pool of advice columns, a
+/// single instance column and a single constants column, instead of each
+/// `configure` allocating its own. Columns a chip needs for itself, such as the
+/// inverse witness of an is-zero check, are allocated fresh.
+pub(crate) struct CircuitBuilder<'a, F: FieldExt> {
+    meta: &'a mut ConstraintSystem<F>,
+    advice: Vec<Column<Advice>>,
+    instance: Option<Column<Instance>>,
+    constant: Option<Column<Fixed>>,
+}
+
+impl<'a, F: FieldExt> CircuitBuilder<'a, F> {
+    pub(crate) fn new(meta: &'a mut ConstraintSystem<F>) -> Self {
+        Self {
+            meta,
+            advice: vec![],
+            instance: None,
+            constant: None,
+        }
+    }
+
+    pub(crate) fn meta(&mut self) -> &mut ConstraintSystem<F> {
+        self.meta
+    }
+
+    /// Returns the first `N` shared advice columns, allocating any that are missing.
+    pub(crate) fn advice<const N: usize>(&mut self) -> [Column<Advice>; N] {
+        while self.advice.len() < N {
+            let column = self.meta.advice_column();
+            self.advice.push(column);
+        }
+
+        std::array::from_fn(|i| self.advice[i])
+    }
+
+    pub(crate) fn instance(&mut self) -> Column<Instance> {
+        *self.instance.get_or_insert_with(|| self.meta.instance_column())
+    }
+
+    pub(crate) fn constant(&mut self) -> Column<Fixed> {
+        *self.constant.get_or_insert_with(|| self.meta.fixed_column())
+    }
+
+    pub(crate) fn field_chip(&mut self) -> FieldConfig<F> {
+        let advice = self.advice::<2>();
+        let instance = self.instance();
+        let constant = self.constant();
+
+        FieldChip::configure(self.meta, advice, instance, constant)
+    }
+
+    pub(crate) fn range_check<const RANGE: usize>(&mut self) -> RangeCheckConfig<F, RANGE> {
+        let [value] = self.advice::<1>();
+
+        RangeCheckConfig::configure(self.meta, value)
+    }
+
+    pub(crate) fn is_zero(
+        &mut self,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+    ) -> IsZeroConfig<F> {
+        let value_inv = self.meta.advice_column();
+
+        IsZeroChip::configure(self.meta, q_enable, value, value_inv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    use super::CircuitBuilder;
+    use crate::{
+        analysis::CircuitInfo,
+        numeric::numeric::{FieldChip, FieldConfig, NumericInstructions},
+        range_check::example1::RangeCheckConfig,
+    };
+
+    #[derive(Clone, Debug)]
+    struct ProductInRangeConfig<F: FieldExt> {
+        field: FieldConfig<F>,
+        range: RangeCheckConfig<F, 8>,
+    }
+
+    /// Proves `a * b == c` for a public `c` and that `c` lies in `[0, 8)`.
+    #[derive(Default)]
+    struct ProductInRangeCircuit<F: FieldExt> {
+        a: Value<F>,
+        b: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for ProductInRangeCircuit<F> {
+        type Config = ProductInRangeConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let mut builder = CircuitBuilder::new(meta);
+
+            ProductInRangeConfig {
+                field: builder.field_chip(),
+                range: builder.range_check(),
+            }
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+            let field_chip = FieldChip::<F>::construct(config.field.clone());
+
+            let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+            let c = field_chip.mul(layouter.namespace(|| "a * b"), a, b)?;
+
+            let in_range = config.range.assign(
+                layouter.namespace(|| "range check c"),
+                c.value().copied(),
+            )?;
+            layouter.assign_region(
+                || "c is range checked",
+                |mut region| {
+                    let c = c.0.copy_advice(|| "c", &mut region, config.field.advice[0], 0)?;
+                    region.constrain_equal(c.cell(), in_range.0.cell())
+                },
+            )?;
+
+            field_chip.expose_public(layouter.namespace(|| "expose c"), c, 0)
+        }
+    }
+
+    #[test]
+    fn builder_two_chips() {
+        let k = 4;
+        let circuit = |a: u64, b: u64| ProductInRangeCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        };
+
+        // Both chips share the builder's two advice columns, and the field chip
+        // gets its instance and constants columns from it.
+        let info = CircuitInfo::collect::<Fp, ProductInRangeCircuit<Fp>>();
+        assert_eq!(info.num_advice_columns, 2);
+        assert_eq!(info.num_instance_columns, 1);
+        assert_eq!(info.num_fixed_columns, 1);
+
+        // c is copied from the field chip's region into the range chip's.
+        let prover = MockProver::run(k, &circuit(2, 3), vec![vec![Fp::from(6)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(2, 3), vec![vec![Fp::from(7)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 9 reaches the range chip through the same copy and is out of range.
+        let prover = MockProver::run(k, &circuit(3, 3), vec![vec![Fp::from(9)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -1 +1 @@
-mod fibonacci1;
\ No newline at end of file
+pub(crate) mod fibonacci1;
\ No newline at end of file
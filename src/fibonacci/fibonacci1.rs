@@ -7,8 +7,10 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
+type FirstRow<F> = (AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>);
+
 #[derive(Clone, Debug)]
-struct FibonacciConfig {
+pub(crate) struct FibonacciConfig {
     col_a: Column<Advice>,
     col_b: Column<Advice>,
     col_c: Column<Advice>,
@@ -63,7 +65,7 @@ impl<F: FieldExt> FibonacciChip<F> {
     fn assign_first_row(
         &self,
         mut layouter: impl Layouter<F>
-    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+    ) -> Result<FirstRow<F>, Error> {
         layouter.assign_region(
             || "first row", 
             |mut region| {
@@ -144,7 +146,8 @@ impl<F: FieldExt> FibonacciChip<F> {
 }
 
 #[derive(Default)]
-struct MyCircuit<F>(PhantomData<F>);
+#[allow(dead_code)]
+pub(crate) struct MyCircuit<F>(pub(crate) PhantomData<F>);
 
 impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
     type Config = FibonacciConfig;
@@ -1,2 +1,3 @@
-mod is_zero_gadget;
-mod is_zero;
\ No newline at end of file
+pub(crate) mod is_zero_gadget;
+#[allow(clippy::module_inception)]
+mod is_zero;
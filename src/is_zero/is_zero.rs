@@ -6,7 +6,7 @@ use crate::is_zero::is_zero_gadget::{
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Selector},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Instance, Selector},
     poly::Rotation,
 };
 
@@ -88,6 +88,7 @@ impl<F: FieldExt> ComposeChip<F> {
 }
 
 #[derive(Default)]
+#[allow(dead_code)]
 struct ComposeCircuit<F> {
     a: F,
     b: F,
@@ -115,6 +116,151 @@ impl<F: FieldExt> Circuit<F> for ComposeCircuit<F> {
     }
 }
 
+#[derive(Clone, Debug)]
+struct MultiLimbConfig<F: FieldExt> {
+    limbs: [Column<Advice>; 2],
+    output: Column<Advice>,
+    selector: Selector,
+    limbs_are_zero: IsZeroConfig<F>,
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct MultiLimbCircuit<F> {
+    limbs: [F; 2],
+    output: F,
+}
+
+impl<F: FieldExt> Circuit<F> for MultiLimbCircuit<F> {
+    type Config = MultiLimbConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let selector = meta.selector();
+        let limbs = [meta.advice_column(), meta.advice_column()];
+        let output = meta.advice_column();
+
+        let is_zero_advice_column = meta.advice_column();
+
+        let limbs_are_zero = IsZeroChip::configure_multilimb(
+            meta,
+            |meta| meta.query_selector(selector),
+            &limbs,
+            is_zero_advice_column,
+        );
+
+        meta.create_gate("output = limbs == 0", |meta| {
+            let s = meta.query_selector(selector);
+            let output = meta.query_advice(output, Rotation::cur());
+
+            vec![s * (output - limbs_are_zero.expr())]
+        });
+
+        MultiLimbConfig { limbs, output, selector, limbs_are_zero }
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let is_zero_chip = IsZeroChip::construct(config.limbs_are_zero.clone());
+
+        layouter.assign_region(
+            || "output = limbs == 0",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "limb 0", config.limbs[0], 0, || Value::known(self.limbs[0]))?;
+                region.assign_advice(|| "limb 1", config.limbs[1], 0, || Value::known(self.limbs[1]))?;
+                is_zero_chip.assign_multilimb(&mut region, 0, &self.limbs.map(Value::known))?;
+                region.assign_advice(|| "output", config.output, 0, || Value::known(self.output))?;
+
+                Ok(())
+            },
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FlagConfig<F: FieldExt> {
+    value: Column<Advice>,
+    c: Column<Advice>,
+    output: Column<Advice>,
+    instance: Column<Instance>,
+    selector: Selector,
+    value_is_zero: IsZeroConfig<F>,
+}
+
+/// Computes `value == 0 ? c : 0` with the flag's expression, and exposes the
+/// flag cell itself as a public input.
+#[derive(Default)]
+#[allow(dead_code)]
+struct FlagCircuit<F> {
+    value: F,
+    c: F,
+}
+
+impl<F: FieldExt> Circuit<F> for FlagCircuit<F> {
+    type Config = FlagConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let selector = meta.selector();
+        let value = meta.advice_column();
+        let c = meta.advice_column();
+        let output = meta.advice_column();
+        let flag = meta.advice_column();
+        let instance = meta.instance_column();
+
+        meta.enable_equality(instance);
+
+        let is_zero_advice_column = meta.advice_column();
+
+        let value_is_zero = IsZeroChip::configure_with_flag(
+            meta,
+            |meta| meta.query_selector(selector),
+            |meta| meta.query_advice(value, Rotation::cur()),
+            is_zero_advice_column,
+            flag,
+        );
+
+        meta.create_gate("output = value == 0 ? c : 0", |meta| {
+            let s = meta.query_selector(selector);
+            let c = meta.query_advice(c, Rotation::cur());
+            let output = meta.query_advice(output, Rotation::cur());
+
+            vec![s * (output - value_is_zero.expr() * c)]
+        });
+
+        FlagConfig { value, c, output, instance, selector, value_is_zero }
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let is_zero_chip = IsZeroChip::construct(config.value_is_zero.clone());
+
+        let flag = layouter.assign_region(
+            || "output = value == 0 ? c : 0",
+            |mut region| {
+                config.selector.enable(&mut region, 0)?;
+                region.assign_advice(|| "value", config.value, 0, || Value::known(self.value))?;
+                region.assign_advice(|| "c", config.c, 0, || Value::known(self.c))?;
+                let (flag, _) = is_zero_chip.assign_with_flag(&mut region, 0, Value::known(self.value))?;
+
+                let output = if self.value == F::zero() { self.c } else { F::zero() };
+                region.assign_advice(|| "output", config.output, 0, || Value::known(output))?;
+
+                Ok(flag)
+            },
+        )?;
+
+        layouter.constrain_instance(flag.cell(), config.instance, 0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,4 +282,55 @@ mod tests {
         let prover = MockProver::run(k, &circuit, vec![]).unwrap();
         prover.assert_satisfied();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_is_zero_multilimb() {
+        let k = 4;
+
+        let circuit = |limbs: [u64; 2], output: u64| MultiLimbCircuit {
+            limbs: limbs.map(Fp::from),
+            output: Fp::from(output),
+        };
+
+        for (limbs, output) in [([0, 0], 1), ([0, 5], 0), ([3, 0], 0), ([3, 5], 0)] {
+            let prover = MockProver::run(k, &circuit(limbs, output), vec![]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        for (limbs, output) in [([0, 5], 1), ([3, 0], 1), ([0, 0], 0)] {
+            let prover = MockProver::run(k, &circuit(limbs, output), vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        // Limbs summing to zero are still nonzero limbs.
+        let cancelling = |output: u64| MultiLimbCircuit {
+            limbs: [Fp::one(), -Fp::one()],
+            output: Fp::from(output),
+        };
+
+        let prover = MockProver::run(k, &cancelling(0), vec![]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &cancelling(1), vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_is_zero_with_flag() {
+        let k = 4;
+
+        let circuit = |value: u64| FlagCircuit {
+            value: Fp::from(value),
+            c: Fp::from(7),
+        };
+
+        let prover = MockProver::run(k, &circuit(0), vec![vec![Fp::one()]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(3), vec![vec![Fp::zero()]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(3), vec![vec![Fp::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -5,9 +5,13 @@ use halo2_proofs::{
     poly::Rotation,
 };
 
+/// A witnessed is-zero flag, together with the expression it is constrained to.
+type Flag<F> = (AssignedCell<Assigned<F>, F>, Expression<F>);
+
 #[derive(Clone, Debug)]
 pub struct IsZeroConfig<F> {
     value_inv: Column<Advice>,
+    flag: Option<Column<Advice>>,
     is_zero_expr: Expression<F>,
 }
 
@@ -46,6 +50,84 @@ impl<F: FieldExt> IsZeroChip<F> {
 
         IsZeroConfig {
             value_inv,
+            flag: None,
+            is_zero_expr,
+        }
+    }
+
+    /// Configures an is-zero check that also witnesses its result in `flag`, so
+    /// the result can be copied into other regions as well as used in gates.
+    pub fn configure_with_flag(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        value_inv: Column<Advice>,
+        flag: Column<Advice>,
+    ) -> IsZeroConfig<F> {
+        let mut is_zero_expr = Expression::Constant(F::zero());
+
+        meta.enable_equality(flag);
+
+        meta.create_gate("is zero with flag", |meta| {
+            let value = value(meta);
+            let q_enable = q_enable(meta);
+            let value_inv = meta.query_advice(value_inv, Rotation::cur());
+            let flag = meta.query_advice(flag, Rotation::cur());
+
+            is_zero_expr = Expression::Constant(F::one()) - value.clone() * value_inv;
+
+            vec![
+                q_enable.clone() * value * is_zero_expr.clone(),
+                q_enable * (flag - is_zero_expr.clone()),
+            ]
+        });
+
+        IsZeroConfig {
+            value_inv,
+            flag: Some(flag),
+            is_zero_expr,
+        }
+    }
+
+    /// Configures an is-zero check over a value split across `limbs`. The value
+    /// is zero only if every limb is zero, so each limb gets its own inverse:
+    /// the inverse of `limbs[i]` is assigned to `value_inv` at rotation `i`.
+    /// An enabled row at `offset` therefore claims `value_inv` on rows
+    /// `offset..offset + limbs.len()`, and nothing else may be assigned there.
+    ///
+    /// The expression is the product of the per-limb `1 - limb * limb_inv`
+    /// terms, which is one exactly when every limb is zero. That already
+    /// implies the limbs sum to zero, so there is no separate sum constraint; a
+    /// sum check alone would accept limbs like `[1, -1]`.
+    pub fn configure_multilimb(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
+        limbs: &[Column<Advice>],
+        value_inv: Column<Advice>,
+    ) -> IsZeroConfig<F> {
+        let mut is_zero_expr = Expression::Constant(F::one());
+
+        meta.create_gate("is zero multilimb", |meta| {
+            let q_enable = q_enable(meta);
+
+            limbs
+                .iter()
+                .enumerate()
+                .map(|(i, limb)| {
+                    let limb = meta.query_advice(*limb, Rotation::cur());
+                    let limb_inv = meta.query_advice(value_inv, Rotation(i as i32));
+                    let limb_is_zero = Expression::Constant(F::one()) - limb.clone() * limb_inv;
+
+                    is_zero_expr = is_zero_expr.clone() * limb_is_zero.clone();
+
+                    q_enable.clone() * limb * limb_is_zero
+                })
+                .collect::<Vec<_>>()
+        });
+
+        IsZeroConfig {
+            value_inv,
+            flag: None,
             is_zero_expr,
         }
     }
@@ -54,9 +136,9 @@ impl<F: FieldExt> IsZeroChip<F> {
         &self,
         region: &mut Region<'_, F>,
         offset: usize,
-        value: Value<F>
+        value: Value<impl Into<Assigned<F>>>
     ) -> Result<(), Error> {
-        let value_inv = value.map(|value| value.invert().unwrap_or(F::zero()));
+        let value_inv = value.map(|value| value.into().invert());
         region.assign_advice(
             || "value inv", 
             self.config.value_inv, 
@@ -66,4 +148,39 @@ impl<F: FieldExt> IsZeroChip<F> {
 
         Ok(())
     }
+
+    /// Assigns the inverse and the flag for a config built by
+    /// `configure_with_flag`, returning the flag cell together with its expression.
+    pub fn assign_with_flag(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: Value<impl Into<Assigned<F>>>,
+    ) -> Result<Flag<F>, Error> {
+        let flag = self.config.flag.ok_or(Error::Synthesis)?;
+
+        let value = value.map(Into::into);
+        self.assign(region, offset, value)?;
+
+        let is_zero = value.map(|value| {
+            Assigned::from(if value.is_zero_vartime() { F::one() } else { F::zero() })
+        });
+        let cell = region.assign_advice(|| "is zero flag", flag, offset, || is_zero)?;
+
+        Ok((cell, self.config.expr()))
+    }
+
+    /// Assigns the limb inverses for a config built by `configure_multilimb`.
+    pub fn assign_multilimb(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        limbs: &[Value<F>],
+    ) -> Result<(), Error> {
+        for (i, limb) in limbs.iter().enumerate() {
+            self.assign(region, offset + i, *limb)?;
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,4 +1,16 @@
 mod numeric;
 mod fibonacci;
 mod is_zero;
-mod range_check;
\ No newline at end of file
+mod range_check;
+// Tooling for the example circuits, so far only driven from their tests.
+#[allow(dead_code)]
+mod proving;
+#[allow(dead_code)]
+mod builder;
+#[allow(dead_code)]
+mod analysis;
+
+/// The numeric chip and its instructions, for reuse in other circuits.
+pub mod prelude {
+    pub use crate::numeric::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+}
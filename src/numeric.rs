@@ -1 +1,35 @@
-mod numeric;
+#[allow(clippy::module_inception)]
+pub(crate) mod numeric;
+mod bitwise;
+mod cdf_lookup;
+mod mini_vm;
+mod weighted_median;
+mod eval_proof;
+mod bounded_counter;
+mod mod_reduce;
+mod lcg;
+mod private_dot;
+mod affine_cipher;
+mod popcount;
+mod fenwick;
+mod catalan;
+mod commit_list;
+mod bloom;
+mod shuffle;
+mod sbox;
+mod monotonic;
+mod nn_layer;
+mod luhn;
+mod factorial;
+mod collatz;
+mod confidential;
+mod crc;
+mod dfa;
+mod sorting_network;
+mod consensus;
+mod convolution;
+mod huffman;
+mod dh;
+mod histogram;
+mod record_checksum;
+mod expr_eval;
@@ -0,0 +1,138 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::{
+    mod_reduce::ModReduceChip,
+    numeric::{FieldChip, FieldConfig, NumericInstructions, Number},
+};
+
+/// Encrypts with the affine cipher `E(x) = (a * x + b) mod n`.
+///
+/// Decryption needs `a` to be invertible modulo `n`, so the chip also witnesses
+/// `a^-1 mod n` and proves `a * a^-1 == 1 (mod n)`.
+struct AffineCipherChip<F: FieldExt> {
+    config: FieldConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> AffineCipherChip<F> {
+    fn construct(config: FieldConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn field_chip(&self) -> FieldChip<F> {
+        FieldChip::construct(self.config.clone())
+    }
+
+    fn mod_chip(&self) -> ModReduceChip<F> {
+        ModReduceChip::construct(self.config.clone())
+    }
+
+    /// Returns the ciphertext of `plaintext`, where `a * plaintext + b` divided
+    /// by `n` and `n` itself fit in `BITS` bits.
+    fn affine_cipher<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        plaintext: Number<F>,
+        a: F,
+        b: F,
+        n: F,
+    ) -> Result<Number<F>, Error> {
+        let field_chip = self.field_chip();
+        let mod_chip = self.mod_chip();
+
+        // a is a circuit constant, but its inverse is still witnessed so that
+        // the circuit is unsatisfiable for a key that cannot be decrypted.
+        let a_const = field_chip.load_constant(layouter.namespace(|| "a"), a)?;
+        mod_chip.mod_inverse::<BITS>(layouter.namespace(|| "a^-1 mod n"), a_const, n)?;
+
+        let ciphertext = field_chip.mul_by_constant(layouter.namespace(|| "a * x"), plaintext, a)?;
+        let ciphertext = field_chip.add_constant(layouter.namespace(|| "a * x + b"), ciphertext, b)?;
+        mod_chip.reduce::<BITS>(layouter.namespace(|| "mod n"), ciphertext, n)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct AffineCipherCircuit<F: FieldExt> {
+    plaintext: Value<F>,
+    a: u64,
+    b: u64,
+    n: u64,
+}
+
+impl<F: FieldExt> Circuit<F> for AffineCipherCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            plaintext: Value::unknown(),
+            ..*self
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = AffineCipherChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        let plaintext = field_chip.load_private(layouter.namespace(|| "load plaintext"), self.plaintext)?;
+        let ciphertext = chip.affine_cipher::<8>(
+            layouter.namespace(|| "encrypt"),
+            plaintext,
+            F::from(self.a),
+            F::from(self.b),
+            F::from(self.n),
+        )?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose ciphertext"), ciphertext, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::AffineCipherCircuit;
+
+    #[test]
+    fn affine_cipher() {
+        let k = 7;
+        let circuit = |plaintext: u64, a: u64| AffineCipherCircuit {
+            plaintext: Value::known(Fp::from(plaintext)),
+            a,
+            b: 8,
+            n: 26,
+        };
+
+        // (5 * 7 + 8) mod 26 = 17
+        let prover = MockProver::run(k, &circuit(7, 5), vec![vec![Fp::from(17)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(7, 5), vec![vec![Fp::from(18)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 13 shares a factor with 26 and has no inverse: (13 * 7 + 8) mod 26 = 21
+        let prover = MockProver::run(k, &circuit(7, 13), vec![vec![Fp::from(21)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,614 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Boolean operations on single bits, laid out on the advice columns of a
+/// `FieldConfig` so they compose with its decomposition instructions.
+#[derive(Clone, Debug)]
+pub(super) struct BitwiseConfig<F: FieldExt> {
+    pub(super) field: FieldConfig<F>,
+    s_and: Selector,
+    s_or: Selector,
+    s_xor: Selector,
+}
+
+pub(super) struct BitwiseChip<F: FieldExt> {
+    config: BitwiseConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> BitwiseChip<F> {
+    pub(super) fn construct(config: BitwiseConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(super) fn configure(meta: &mut ConstraintSystem<F>, field: FieldConfig<F>) -> BitwiseConfig<F> {
+        let advice = field.advice;
+        let s_and = meta.selector();
+        let s_or = meta.selector();
+        let s_xor = meta.selector();
+
+        type BitExpr<F> = fn(Expression<F>, Expression<F>) -> Expression<F>;
+
+        let mut bitwise_gate = |name: &'static str, selector: Selector, op: BitExpr<F>| {
+            meta.create_gate(name, |meta| {
+                let lhs = meta.query_advice(advice[0], Rotation::cur());
+                let rhs = meta.query_advice(advice[1], Rotation::cur());
+                let out = meta.query_advice(advice[0], Rotation::next());
+                let s = meta.query_selector(selector);
+                let one = Expression::Constant(F::one());
+
+                vec![
+                    s.clone() * lhs.clone() * (one.clone() - lhs.clone()),
+                    s.clone() * rhs.clone() * (one - rhs.clone()),
+                    s * (op(lhs, rhs) - out),
+                ]
+            });
+        };
+
+        bitwise_gate("and", s_and, |a, b| a * b);
+        bitwise_gate("or", s_or, |a, b| a.clone() + b.clone() - a * b);
+        bitwise_gate("xor", s_xor, |a, b| {
+            a.clone() + b.clone() - Expression::Constant(F::from(2)) * a * b
+        });
+
+        BitwiseConfig {
+            field,
+            s_and,
+            s_or,
+            s_xor,
+        }
+    }
+
+    fn field_chip(&self) -> FieldChip<F> {
+        FieldChip::construct(self.config.field.clone())
+    }
+
+    fn bit_op(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &'static str,
+        selector: Selector,
+        a: Number<F>,
+        b: Number<F>,
+        op: fn(F, F) -> F,
+    ) -> Result<Number<F>, Error> {
+        let advice = self.config.field.advice;
+
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "lhs", &mut region, advice[0], 0)?;
+                b.0.copy_advice(|| "rhs", &mut region, advice[1], 0)?;
+
+                let value = a.evaluate().as_ref().zip(b.evaluate().as_ref()).map(|(a, b)| op(*a, *b));
+
+                region
+                    .assign_advice(|| name, advice[0], 1, || value.map(Assigned::from))
+                    .map(Number)
+            },
+        )
+    }
+
+    pub(super) fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.bit_op(layouter, "a & b", self.config.s_and, a, b, |a, b| a * b)
+    }
+
+    pub(super) fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.bit_op(layouter, "a | b", self.config.s_or, a, b, |a, b| a + b - a * b)
+    }
+
+    pub(super) fn xor(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        self.bit_op(layouter, "a ^ b", self.config.s_xor, a, b, |a, b| a + b - (a * b).double())
+    }
+
+    /// Returns the bitwise AND of two `BITS`-bit words.
+    pub(super) fn bitwise_and_word<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        let field_chip = self.field_chip();
+
+        let a_bits = field_chip.decompose::<BITS>(layouter.namespace(|| "decompose a"), a)?;
+        let b_bits = field_chip.decompose::<BITS>(layouter.namespace(|| "decompose b"), b)?;
+
+        let bits = a_bits
+            .into_iter()
+            .zip(b_bits)
+            .map(|(a, b)| self.and(layouter.namespace(|| "a_i & b_i"), a, b))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        field_chip.compose(layouter.namespace(|| "compose"), &bits)
+    }
+
+    /// Returns the XOR of all `BITS`-bit `words`, folding them one bit position
+    /// at a time.
+    pub(super) fn xor_reduce_word<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        words: &[Number<F>],
+    ) -> Result<Number<F>, Error> {
+        let field_chip = self.field_chip();
+
+        let words = words
+            .iter()
+            .map(|word| field_chip.decompose::<BITS>(layouter.namespace(|| "decompose word"), word.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (first, rest) = words.split_first().ok_or(Error::Synthesis)?;
+
+        let bits = rest.iter().try_fold(first.clone(), |acc, bits| {
+            acc.into_iter()
+                .zip(bits.iter().cloned())
+                .map(|(a, b)| self.xor(layouter.namespace(|| "acc_i ^ w_i"), a, b))
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        field_chip.compose(layouter.namespace(|| "compose"), &bits)
+    }
+
+    /// Constrains the `BITS`-bit words `a` and `b` to differ in at most `d` bit
+    /// positions: the set bits of `a ^ b` are counted and compared against `d`.
+    pub(super) fn assert_hamming_distance_le<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        b: Number<F>,
+        d: u64,
+    ) -> Result<(), Error> {
+        let field_chip = self.field_chip();
+
+        let a_bits = field_chip.decompose::<BITS>(layouter.namespace(|| "decompose a"), a)?;
+        let b_bits = field_chip.decompose::<BITS>(layouter.namespace(|| "decompose b"), b)?;
+        let diff = a_bits
+            .into_iter()
+            .zip(b_bits)
+            .map(|(a, b)| self.xor(layouter.namespace(|| "a_i ^ b_i"), a, b))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The count is at most BITS, which always fits in BITS bits.
+        let distance = field_chip.accumulate(layouter.namespace(|| "count differing bits"), &diff)?;
+        let d = field_chip.load_constant(layouter.namespace(|| "d"), F::from(d))?;
+        field_chip.assert_less_equal::<BITS>(layouter.namespace(|| "distance <= d"), distance, d)
+    }
+
+    /// Returns 1 if any of `bits` is set and 0 otherwise. Each bit passes through
+    /// an OR gate, which also checks that it is boolean.
+    pub(super) fn or_reduce(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[Number<F>],
+    ) -> Result<Number<F>, Error> {
+        let zero = self.field_chip().load_constant(layouter.namespace(|| "zero"), F::zero())?;
+
+        bits.iter().try_fold(zero, |acc, bit| {
+            self.or(layouter.namespace(|| "acc | b_i"), acc, bit.clone())
+        })
+    }
+
+    /// Returns the Gray-code bits of a `BITS`-bit value, least significant first.
+    ///
+    /// Bit `i` is `b_i ^ b_{i+1}` of the binary decomposition, and the top bit is
+    /// the top binary bit itself.
+    pub(super) fn to_gray_code<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Number<F>,
+    ) -> Result<Vec<Number<F>>, Error> {
+        let bits = self
+            .field_chip()
+            .decompose::<BITS>(layouter.namespace(|| "decompose"), value)?;
+
+        let mut gray = bits
+            .windows(2)
+            .map(|pair| {
+                self.xor(layouter.namespace(|| "b_i ^ b_i+1"), pair[0].clone(), pair[1].clone())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        gray.extend(bits.last().cloned());
+
+        Ok(gray)
+    }
+
+    /// Returns the value whose Gray code is `gray_bits`, `BITS` bits given least
+    /// significant first, inverting [`Self::to_gray_code`].
+    ///
+    /// Binary bit `i` is the prefix XOR `g_i ^ b_{i+1}` taken from the most
+    /// significant bit down. Every Gray bit enters an XOR, which checks that it is
+    /// boolean, so `BITS` is at least 2.
+    pub(super) fn gray_to_binary<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        gray_bits: &[Number<F>],
+    ) -> Result<Number<F>, Error> {
+        assert!(BITS >= 2);
+        if gray_bits.len() != BITS {
+            return Err(Error::Synthesis);
+        }
+
+        let (top, rest) = gray_bits.split_last().unwrap();
+        let mut bits = vec![top.clone()];
+        for gray in rest.iter().rev() {
+            let next = bits.last().unwrap().clone();
+            bits.push(self.xor(layouter.namespace(|| "g_i ^ b_i+1"), gray.clone(), next)?);
+        }
+        bits.reverse();
+
+        self.field_chip().compose(layouter.namespace(|| "compose binary"), &bits)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct BitwiseCircuit<F: FieldExt> {
+    a: Value<F>,
+    b: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for BitwiseCircuit<F> {
+    type Config = BitwiseConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let field = FieldChip::configure(meta, advice, instance, constant);
+        BitwiseChip::configure(meta, field)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BitwiseChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+
+        let a_and_b = chip.bitwise_and_word::<4>(layouter.namespace(|| "a & b"), a, b)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose a & b"), a_and_b, 0)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct GrayCodeCircuit<F: FieldExt> {
+    value: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for GrayCodeCircuit<F> {
+    type Config = BitwiseConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BitwiseCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BitwiseChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        let value = field_chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+        let gray = chip.to_gray_code::<4>(layouter.namespace(|| "gray code"), value)?;
+        let gray = field_chip.compose(layouter.namespace(|| "compose gray code"), &gray)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose gray code"), gray, 0)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct GrayToBinaryCircuit<F: FieldExt> {
+    gray_bits: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for GrayToBinaryCircuit<F> {
+    type Config = BitwiseConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            gray_bits: vec![Value::unknown(); self.gray_bits.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BitwiseCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BitwiseChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        let gray_bits = self
+            .gray_bits
+            .iter()
+            .map(|bit| field_chip.load_private(layouter.namespace(|| "load gray bit"), *bit))
+            .collect::<Result<Vec<_>, _>>()?;
+        let value = chip.gray_to_binary::<4>(layouter.namespace(|| "gray to binary"), &gray_bits)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose value"), value, 0)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct HammingDistanceCircuit<F: FieldExt> {
+    a: Value<F>,
+    b: Value<F>,
+    d: u64,
+}
+
+impl<F: FieldExt> Circuit<F> for HammingDistanceCircuit<F> {
+    type Config = BitwiseConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            d: self.d,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BitwiseCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BitwiseChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+
+        chip.assert_hamming_distance_le::<8>(layouter.namespace(|| "hamming distance"), a, b, self.d)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct OrReduceCircuit<F: FieldExt> {
+    bits: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for OrReduceCircuit<F> {
+    type Config = BitwiseConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            bits: vec![Value::unknown(); self.bits.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BitwiseCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BitwiseChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        let bits = self
+            .bits
+            .iter()
+            .map(|bit| field_chip.load_private(layouter.namespace(|| "load bit"), *bit))
+            .collect::<Result<Vec<_>, _>>()?;
+        let any = chip.or_reduce(layouter.namespace(|| "or reduce"), &bits)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose any"), any, 0)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct XorReduceCircuit<F: FieldExt> {
+    words: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for XorReduceCircuit<F> {
+    type Config = BitwiseConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            words: vec![Value::unknown(); self.words.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BitwiseCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BitwiseChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        let words = self
+            .words
+            .iter()
+            .map(|word| field_chip.load_private(layouter.namespace(|| "load word"), *word))
+            .collect::<Result<Vec<_>, _>>()?;
+        let checksum = chip.xor_reduce_word::<4>(layouter.namespace(|| "xor reduce"), &words)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose checksum"), checksum, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::{
+        BitwiseCircuit, GrayCodeCircuit, GrayToBinaryCircuit, HammingDistanceCircuit, OrReduceCircuit,
+        XorReduceCircuit,
+    };
+
+    #[test]
+    fn bitwise_and_word() {
+        let k = 6;
+
+        let circuit = BitwiseCircuit {
+            a: Value::known(Fp::from(0b1100)),
+            b: Value::known(Fp::from(0b1010)),
+        };
+
+        let mut public_input = vec![Fp::from(0b1000)];
+
+        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        public_input[0] = Fp::from(0b1110);
+        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn to_gray_code() {
+        let k = 6;
+
+        for (value, gray) in [(0b0110, 0b0101), (0b0000, 0b0000), (0b1111, 0b1000), (0b1010, 0b1111)] {
+            let circuit = GrayCodeCircuit {
+                value: Value::known(Fp::from(value)),
+            };
+
+            let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(gray)]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let circuit = GrayCodeCircuit {
+            value: Value::known(Fp::from(0b0110)),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0b0110)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn gray_to_binary() {
+        let k = 6;
+        // Gray bits are given least significant first.
+        let circuit = |gray: u64| GrayToBinaryCircuit {
+            gray_bits: (0..4).map(|i| Value::known(Fp::from(gray >> i & 1))).collect(),
+        };
+
+        for (gray, value) in [(0b0101, 0b0110), (0b0000, 0b0000), (0b1000, 0b1111), (0b1111, 0b1010)] {
+            let prover = MockProver::run(k, &circuit(gray), vec![vec![Fp::from(value)]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let prover = MockProver::run(k, &circuit(0b0101), vec![vec![Fp::from(0b0101)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Gray bits must be boolean.
+        let circuit = GrayToBinaryCircuit {
+            gray_bits: [0, 2, 0, 0].map(|bit| Value::known(Fp::from(bit))).to_vec(),
+        };
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(0b0010)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn assert_hamming_distance_le() {
+        let k = 7;
+        let circuit = |a: u64, b: u64, d: u64| HammingDistanceCircuit {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+            d,
+        };
+
+        // The words differ in bits 1 and 6.
+        let (a, b) = (0b1011_0110, 0b1111_0100);
+        for d in [2, 3, 8] {
+            let prover = MockProver::run(k, &circuit(a, b, d), vec![vec![]]).unwrap();
+            prover.assert_satisfied();
+        }
+        for d in [0, 1] {
+            let prover = MockProver::run(k, &circuit(a, b, d), vec![vec![]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        let prover = MockProver::run(k, &circuit(a, a, 0), vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn or_reduce() {
+        let k = 5;
+        let circuit = |bits: &[u64]| OrReduceCircuit {
+            bits: bits.iter().map(|bit| Value::known(Fp::from(*bit))).collect(),
+        };
+
+        for (bits, any) in [(&[0, 0, 0], 0), (&[0, 1, 0], 1), (&[1, 1, 1], 1)] {
+            let prover = MockProver::run(k, &circuit(bits), vec![vec![Fp::from(any)]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let prover = MockProver::run(k, &circuit(&[0, 1, 0]), vec![vec![Fp::zero()]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Inputs must be boolean.
+        let prover = MockProver::run(k, &circuit(&[0, 2, 0]), vec![vec![Fp::from(2)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn xor_reduce_word() {
+        let k = 7;
+        let circuit = |words: &[u64]| XorReduceCircuit {
+            words: words.iter().map(|word| Value::known(Fp::from(*word))).collect(),
+        };
+
+        for (words, checksum) in [(&[0b1100, 0b1010, 0b0110][..], 0b0000), (&[0b1100, 0b1010], 0b0110)] {
+            let prover = MockProver::run(k, &circuit(words), vec![vec![Fp::from(checksum)]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let public_input = vec![vec![Fp::from(0b1110)]];
+        let prover = MockProver::run(k, &circuit(&[0b1100, 0b1010, 0b0110]), public_input).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 0b10000 does not fit in four bits.
+        let prover = MockProver::run(k, &circuit(&[0b10000, 0b1010]), vec![vec![Fp::from(0b11010)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::{
+    mod_reduce::ModReduceChip,
+    numeric::{FieldChip, FieldConfig, NumericInstructions, Number},
+};
+
+/// Domain separators for the two hash functions `H(value, 1)` and `H(value, 2)`.
+const HASH_KEYS: [u64; 2] = [1, 2];
+
+/// Constrains `value` to be a possible member of the bloom filter `bits`: both
+/// hash indices `H(value, k) mod bits.len()` must point at set bits.
+///
+/// The toy hash must fit in 128 bits for the reduction, with its quotient by
+/// the filter size fitting in `BITS` bits, so `value` has to be small.
+fn assert_bloom_member<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    value: Number<F>,
+    bits: &[Number<F>],
+) -> Result<(), Error> {
+    let size = F::from(bits.len() as u64);
+    let mod_chip = ModReduceChip::construct(chip.config().clone());
+
+    for bit in bits {
+        chip.decompose::<1>(layouter.namespace(|| "bit is boolean"), bit.clone())?;
+    }
+
+    let one = chip.load_constant(layouter.namespace(|| "one"), F::one())?;
+    for key in HASH_KEYS {
+        let key = chip.load_constant(layouter.namespace(|| "hash key"), F::from(key))?;
+        let hash = chip.toy_hash(layouter.namespace(|| "H(value, k)"), value.clone(), key)?;
+        let index = mod_chip.reduce::<BITS>(layouter.namespace(|| "H mod size"), hash, size)?;
+
+        // index < size, so exactly one of the indicators below is set and the
+        // sum selects bits[index].
+        let mut selected = Vec::with_capacity(bits.len());
+        for (j, bit) in bits.iter().enumerate() {
+            let offset =
+                chip.add_constant(layouter.namespace(|| "index - j"), index.clone(), -F::from(j as u64))?;
+            let is_j = chip.is_zero(layouter.namespace(|| "index == j"), offset)?;
+            selected.push(chip.mul(layouter.namespace(|| "[index == j] * bit"), is_j, bit.clone())?);
+        }
+        let selected = chip.accumulate(layouter.namespace(|| "bits[index]"), &selected)?;
+
+        chip.constrain_equal(layouter.namespace(|| "bits[index] == 1"), &selected, &one)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct BloomCircuit<F: FieldExt> {
+    value: Value<F>,
+    bits: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for BloomCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: Value::unknown(),
+            bits: vec![Value::unknown(); self.bits.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+        let bits = self
+            .bits
+            .iter()
+            .map(|bit| chip.load_private(layouter.namespace(|| "load bit"), *bit))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_bloom_member::<F, 64>(&chip, layouter.namespace(|| "bloom member"), value, &bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        arithmetic::FieldExt,
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::{BloomCircuit, HASH_KEYS};
+
+    const SIZE: usize = 16;
+
+    fn indices(value: u64) -> Vec<usize> {
+        HASH_KEYS
+            .iter()
+            .map(|key| {
+                let x = Fp::from(value) + Fp::from(3 * key) + Fp::from(7);
+                let hash = x.square().square() * x;
+                (hash.get_lower_128() % SIZE as u128) as usize
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bloom_membership() {
+        let k = 10;
+        let circuit = |value: u64, bits: &[bool]| BloomCircuit {
+            value: Value::known(Fp::from(value)),
+            bits: bits.iter().map(|bit| Value::known(Fp::from(*bit as u64))).collect(),
+        };
+
+        let mut bits = [false; SIZE];
+        for member in [42, 1000] {
+            for index in indices(member) {
+                bits[index] = true;
+            }
+        }
+
+        for member in [42, 1000] {
+            let prover = MockProver::run(k, &circuit(member, &bits), vec![vec![]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // Clearing one of 42's bits makes it a definite non-member.
+        let mut cleared = bits;
+        cleared[indices(42)[1]] = false;
+        let prover = MockProver::run(k, &circuit(42, &cleared), vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// A counter that, at every step, either increments or holds depending on a
+/// boolean flag, and is proven to stay `<= MAX` after every step.
+#[derive(Clone, Debug)]
+struct BoundedCounterConfig<F: FieldExt> {
+    field: FieldConfig<F>,
+    s_step: Selector,
+}
+
+struct BoundedCounterChip<F: FieldExt, const MAX: u64> {
+    config: BoundedCounterConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const MAX: u64> BoundedCounterChip<F, MAX> {
+    fn construct(config: BoundedCounterConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>, field: FieldConfig<F>) -> BoundedCounterConfig<F> {
+        let advice = field.advice;
+        let s_step = meta.selector();
+
+        // counter_next = counter + flag, with flag boolean: the successor of the
+        // counter when the flag is set, the counter itself otherwise.
+        meta.create_gate("conditional successor", |meta| {
+            let flag = meta.query_advice(advice[0], Rotation::cur());
+            let counter = meta.query_advice(advice[1], Rotation::cur());
+            let counter_next = meta.query_advice(advice[1], Rotation::next());
+            let s_step = meta.query_selector(s_step);
+
+            vec![
+                s_step.clone() * flag.clone() * (Expression::Constant(F::one()) - flag.clone()),
+                s_step * (counter + flag - counter_next),
+            ]
+        });
+
+        BoundedCounterConfig { field, s_step }
+    }
+
+    fn field_chip(&self) -> FieldChip<F> {
+        FieldChip::construct(self.config.field.clone())
+    }
+
+    /// Runs the counter from zero over `flags`, returning its value after each step.
+    fn assign_steps<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        flags: &[Value<F>],
+    ) -> Result<Vec<Number<F>>, Error> {
+        let advice = self.config.field.advice;
+
+        let counters = layouter.assign_region(
+            || "counter steps",
+            |mut region| {
+                let mut counter = region
+                    .assign_advice_from_constant(|| "counter", advice[1], 0, Assigned::from(F::zero()))?;
+                let mut counters = Vec::with_capacity(flags.len());
+
+                for (row, flag) in flags.iter().enumerate() {
+                    self.config.s_step.enable(&mut region, row)?;
+
+                    let flag = region.assign_advice(|| "flag", advice[0], row, || flag.map(Assigned::from))?;
+                    let next = counter.value().copied() + flag.value();
+                    counter = region.assign_advice(|| "counter", advice[1], row + 1, || next)?;
+
+                    counters.push(Number(counter.clone()));
+                }
+
+                Ok(counters)
+            },
+        )?;
+
+        let field_chip = self.field_chip();
+        let max = field_chip.load_constant(layouter.namespace(|| "max"), F::from(MAX))?;
+        for counter in &counters {
+            field_chip.assert_less_equal::<BITS>(
+                layouter.namespace(|| "counter <= max"),
+                counter.clone(),
+                max.clone(),
+            )?;
+        }
+
+        Ok(counters)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct BoundedCounterCircuit<F: FieldExt> {
+    flags: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for BoundedCounterCircuit<F> {
+    type Config = BoundedCounterConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            flags: vec![Value::unknown(); self.flags.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let field = FieldChip::configure(meta, advice, instance, constant);
+        BoundedCounterChip::<F, 3>::configure(meta, field)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = BoundedCounterChip::<F, 3>::construct(config);
+
+        let mut counters = chip.assign_steps::<4>(layouter.namespace(|| "steps"), &self.flags)?;
+        let last = counters.pop().ok_or(Error::Synthesis)?;
+
+        chip.field_chip().expose_public(layouter.namespace(|| "expose counter"), last, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::BoundedCounterCircuit;
+
+    #[test]
+    fn bounded_counter() {
+        let k = 6;
+        let circuit = |flags: &[u64]| BoundedCounterCircuit {
+            flags: flags.iter().map(|flag| Value::known(Fp::from(*flag))).collect(),
+        };
+
+        let prover = MockProver::run(k, &circuit(&[1, 0, 1, 1, 0]), vec![vec![Fp::from(3)]]).unwrap();
+        prover.assert_satisfied();
+
+        // The fourth increment takes the counter past MAX = 3.
+        let prover = MockProver::run(k, &circuit(&[1, 1, 1, 1, 0]), vec![vec![Fp::from(4)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Flags must be boolean.
+        let prover = MockProver::run(k, &circuit(&[2, 0, 0, 0, 0]), vec![vec![Fp::from(2)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
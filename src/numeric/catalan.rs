@@ -0,0 +1,89 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Returns the first `n` Catalan numbers, from `C_0 = 1` via the recurrence
+/// `C_{m+1} = sum_{i=0}^{m} C_i * C_{m-i}`.
+fn catalan<F: FieldExt>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    n: usize,
+) -> Result<Vec<Number<F>>, Error> {
+    let mut seq = Vec::with_capacity(n);
+    if n == 0 {
+        return Ok(seq);
+    }
+    seq.push(chip.load_constant(layouter.namespace(|| "C_0"), F::one())?);
+
+    while seq.len() < n {
+        let m = seq.len() - 1;
+        let products = (0..=m)
+            .map(|i| {
+                chip.mul(layouter.namespace(|| "C_i * C_{m-i}"), seq[i].clone(), seq[m - i].clone())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let next = chip.accumulate(layouter.namespace(|| "C_{m+1}"), &products)?;
+        seq.push(next);
+    }
+
+    Ok(seq)
+}
+
+/// Proves the first `n` Catalan numbers and exposes the last one.
+#[derive(Default)]
+#[allow(dead_code)]
+struct CatalanCircuit {
+    n: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for CatalanCircuit {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self { n: self.n }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let mut seq = catalan(&chip, layouter.namespace(|| "catalan"), self.n)?;
+        let last = seq.pop().ok_or(Error::Synthesis)?;
+
+        chip.expose_public(layouter.namespace(|| "expose C_{n-1}"), last, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::CatalanCircuit;
+
+    #[test]
+    fn catalan() {
+        let k = 7;
+
+        for (n, last) in [1, 1, 2, 5, 14].into_iter().enumerate().map(|(i, c)| (i + 1, c)) {
+            let prover = MockProver::run(k, &CatalanCircuit { n }, vec![vec![Fp::from(last)]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let prover = MockProver::run(k, &CatalanCircuit { n: 5 }, vec![vec![Fp::from(13)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Places a sample into a bucket of a discrete CDF given as prefix sums.
+///
+/// The table holds one row `(i, prefix[i], prefix[i + 1])` per bucket. A claimed
+/// bucket is looked up to fetch its bounds, and the comparators then prove
+/// `prefix[i] <= sample < prefix[i + 1]`.
+#[derive(Clone, Debug)]
+struct CdfConfig<F: FieldExt> {
+    field: FieldConfig<F>,
+    q_lookup: Selector,
+    index: TableColumn,
+    lower: TableColumn,
+    upper: TableColumn,
+}
+
+struct CdfChip<F: FieldExt> {
+    config: CdfConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> CdfChip<F> {
+    fn construct(config: CdfConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>, field: FieldConfig<F>) -> CdfConfig<F> {
+        let advice = field.advice;
+        let q_lookup = meta.complex_selector();
+        let index = meta.lookup_table_column();
+        let lower = meta.lookup_table_column();
+        let upper = meta.lookup_table_column();
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_lookup);
+            let bucket = meta.query_advice(advice[0], Rotation::cur());
+            let lo = meta.query_advice(advice[1], Rotation::cur());
+            let hi = meta.query_advice(advice[0], Rotation::next());
+
+            vec![
+                (q.clone() * bucket, index),
+                (q.clone() * lo, lower),
+                (q * hi, upper),
+            ]
+        });
+
+        CdfConfig {
+            field,
+            q_lookup,
+            index,
+            lower,
+            upper,
+        }
+    }
+
+    fn field_chip(&self) -> FieldChip<F> {
+        FieldChip::construct(self.config.field.clone())
+    }
+
+    fn load(&self, layouter: &mut impl Layouter<F>, prefix: &[u64]) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load cdf table",
+            |mut table| {
+                // The all-zero row is what disabled lookup rows evaluate to. It
+                // describes the empty interval [0, 0), which no sample satisfies.
+                let rows = std::iter::once((0, 0, 0)).chain(
+                    prefix
+                        .windows(2)
+                        .enumerate()
+                        .map(|(i, bounds)| (i as u64, bounds[0], bounds[1])),
+                );
+
+                for (offset, (index, lower, upper)) in rows.enumerate() {
+                    let columns = [
+                        ("index", self.config.index, index),
+                        ("lower", self.config.lower, lower),
+                        ("upper", self.config.upper, upper),
+                    ];
+                    for (name, column, value) in columns {
+                        table.assign_cell(|| name, column, offset, || Value::known(F::from(value)))?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    /// Proves that `sample` falls into `bucket` and returns the bucket index.
+    fn assign_bucket<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        prefix: &[u64],
+        sample: Number<F>,
+        bucket: Value<u64>,
+    ) -> Result<Number<F>, Error> {
+        let advice = self.config.field.advice;
+        let bound = |offset: u64| {
+            bucket.map(|i| Assigned::from(F::from(prefix.get((i + offset) as usize).copied().unwrap_or(0))))
+        };
+
+        let (index, lo, hi) = layouter.assign_region(
+            || "bucket bounds",
+            |mut region| {
+                self.config.q_lookup.enable(&mut region, 0)?;
+
+                let index = region
+                    .assign_advice(|| "bucket", advice[0], 0, || bucket.map(|i| Assigned::from(F::from(i))))?;
+                let lo = region.assign_advice(|| "lower", advice[1], 0, || bound(0))?;
+                let hi = region.assign_advice(|| "upper", advice[0], 1, || bound(1))?;
+
+                Ok((Number(index), Number(lo), Number(hi)))
+            },
+        )?;
+
+        let field_chip = self.field_chip();
+        field_chip.assert_less_equal::<BITS>(layouter.namespace(|| "lower <= sample"), lo, sample.clone())?;
+        field_chip.assert_less_than::<BITS>(layouter.namespace(|| "sample < upper"), sample, hi)?;
+
+        Ok(index)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct CdfCircuit<F: FieldExt> {
+    prefix: Vec<u64>,
+    sample: Value<F>,
+    bucket: Value<u64>,
+}
+
+impl<F: FieldExt> Circuit<F> for CdfCircuit<F> {
+    type Config = CdfConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            prefix: self.prefix.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let field = FieldChip::configure(meta, advice, instance, constant);
+        CdfChip::configure(meta, field)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = CdfChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        chip.load(&mut layouter, &self.prefix)?;
+
+        let sample = field_chip.load_private(layouter.namespace(|| "load sample"), self.sample)?;
+        let bucket = chip.assign_bucket::<8>(
+            layouter.namespace(|| "bucket"),
+            &self.prefix,
+            sample,
+            self.bucket,
+        )?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose bucket"), bucket, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::CdfCircuit;
+
+    #[test]
+    fn cdf_lookup() {
+        let k = 6;
+        let prefix = vec![0, 10, 30, 60, 100];
+
+        let circuit = |sample: u64, bucket: u64| CdfCircuit {
+            prefix: prefix.clone(),
+            sample: Value::known(Fp::from(sample)),
+            bucket: Value::known(bucket),
+        };
+
+        for (sample, bucket) in [(0, 0), (35, 2), (59, 2), (60, 3), (99, 3)] {
+            let public_input = vec![Fp::from(bucket)];
+            let prover = MockProver::run(k, &circuit(sample, bucket), vec![public_input]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let prover = MockProver::run(k, &circuit(35, 1), vec![vec![Fp::from(1)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let prover = MockProver::run(k, &circuit(100, 3), vec![vec![Fp::from(3)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Returns the Collatz successor of a `BITS`-bit `n`: `n / 2` if `n` is even
+/// and `3n + 1` if it is odd.
+///
+/// The parity is the low bit of the decomposition of `n`, and the remaining
+/// bits recompose to `n / 2`. The successor is then selected as
+/// `n / 2 + parity * (3n + 1 - n / 2)`.
+fn collatz_step<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    n: Number<F>,
+) -> Result<Number<F>, Error> {
+    let bits = chip.decompose::<BITS>(layouter.namespace(|| "decompose n"), n.clone())?;
+    let (parity, high) = bits.split_first().ok_or(Error::Synthesis)?;
+    let half = chip.compose(layouter.namespace(|| "n / 2"), high)?;
+
+    let tripled = chip.mul_by_constant(layouter.namespace(|| "3n"), n, F::from(3))?;
+    let tripled = chip.add_constant(layouter.namespace(|| "3n + 1"), tripled, F::one())?;
+
+    let diff = chip.sub(layouter.namespace(|| "3n + 1 - n / 2"), tripled, half.clone())?;
+    let diff = chip.mul(layouter.namespace(|| "parity * diff"), parity.clone(), diff)?;
+    chip.add(layouter.namespace(|| "successor"), half, diff)
+}
+
+/// Constrains `trajectory` to follow the Collatz map step by step and to end
+/// at 1. Every value must fit in `BITS` bits.
+fn assert_collatz<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    trajectory: &[Number<F>],
+) -> Result<(), Error> {
+    let last = trajectory.last().ok_or(Error::Synthesis)?;
+
+    for pair in trajectory.windows(2) {
+        let next = collatz_step::<F, BITS>(chip, layouter.namespace(|| "step"), pair[0].clone())?;
+        chip.constrain_equal(layouter.namespace(|| "claimed == step"), &pair[1], &next)?;
+    }
+
+    let one = chip.load_constant(layouter.namespace(|| "one"), F::one())?;
+    chip.constrain_equal(layouter.namespace(|| "ends at 1"), last, &one)
+}
+
+/// Proves that the Collatz trajectory of the public start reaches 1.
+#[derive(Default)]
+#[allow(dead_code)]
+struct CollatzCircuit<F: FieldExt> {
+    trajectory: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for CollatzCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            trajectory: vec![Value::unknown(); self.trajectory.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let trajectory = self
+            .trajectory
+            .iter()
+            .map(|n| chip.load_private(layouter.namespace(|| "load n"), *n))
+            .collect::<Result<Vec<_>, _>>()?;
+        let start = trajectory.first().cloned().ok_or(Error::Synthesis)?;
+
+        assert_collatz::<F, 8>(&chip, layouter.namespace(|| "collatz"), &trajectory)?;
+
+        chip.expose_public(layouter.namespace(|| "expose start"), start, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::CollatzCircuit;
+
+    #[test]
+    fn collatz() {
+        let k = 9;
+        let circuit = |trajectory: &[u64]| CollatzCircuit {
+            trajectory: trajectory.iter().map(|n| Value::known(Fp::from(*n))).collect(),
+        };
+
+        let trajectory = [6, 3, 10, 5, 16, 8, 4, 2, 1];
+        let prover = MockProver::run(k, &circuit(&trajectory), vec![vec![Fp::from(6)]]).unwrap();
+        prover.assert_satisfied();
+
+        // 5 is odd, so its successor is 16, not 15.
+        let broken = [6, 3, 10, 5, 15, 8, 4, 2, 1];
+        let prover = MockProver::run(k, &circuit(&broken), vec![vec![Fp::from(6)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // A valid prefix that stops before reaching 1.
+        let prover = MockProver::run(k, &circuit(&trajectory[..4]), vec![vec![Fp::from(6)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
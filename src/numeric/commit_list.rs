@@ -0,0 +1,93 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Folds `leaves` into a single root, `root = H(... H(H(0, l_0), l_1) ..., l_n)`.
+fn commit_list<F: FieldExt>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    leaves: &[Number<F>],
+) -> Result<Number<F>, Error> {
+    let zero = chip.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+
+    leaves.iter().try_fold(zero, |root, leaf| {
+        chip.toy_hash(layouter.namespace(|| "H(root, leaf)"), root, leaf.clone())
+    })
+}
+
+/// Commits to a private list of leaves: only the root is public, at instance row 0.
+#[derive(Default)]
+#[allow(dead_code)]
+struct CommitListCircuit<F: FieldExt> {
+    leaves: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for CommitListCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            leaves: vec![Value::unknown(); self.leaves.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let leaves = self
+            .leaves
+            .iter()
+            .map(|leaf| chip.load_private(layouter.namespace(|| "load leaf"), *leaf))
+            .collect::<Result<Vec<_>, _>>()?;
+        let root = commit_list(&chip, layouter.namespace(|| "commit"), &leaves)?;
+
+        chip.expose_public(layouter.namespace(|| "expose root"), root, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::CommitListCircuit;
+
+    fn toy_hash(a: Fp, b: Fp) -> Fp {
+        let x = a + Fp::from(3) * b + Fp::from(7);
+        x.square().square() * x
+    }
+
+    #[test]
+    fn commit_list() {
+        let k = 8;
+        let leaves = (1..=8).map(Fp::from).collect::<Vec<_>>();
+        let root = leaves.iter().fold(Fp::zero(), |root, leaf| toy_hash(root, *leaf));
+
+        let circuit = |leaves: &[Fp]| CommitListCircuit {
+            leaves: leaves.iter().map(|leaf| Value::known(*leaf)).collect(),
+        };
+
+        let prover = MockProver::run(k, &circuit(&leaves), vec![vec![root]]).unwrap();
+        prover.assert_satisfied();
+
+        let mut tampered = leaves.clone();
+        tampered[5] += Fp::one();
+        let prover = MockProver::run(k, &circuit(&tampered), vec![vec![root]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
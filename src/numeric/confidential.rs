@@ -0,0 +1,148 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Fixed bases the values are committed against, and the base of the blinding
+/// factor. Real Pedersen commitments use independent group elements; field
+/// elements stand in for them here, so the commitment is neither hiding nor
+/// binding and only shows the shape of the circuit.
+const BASES: [u64; 3] = [0x1234_5678, 0x9abc_def0, 0x0fed_cba9];
+const BLINDING_BASE: u64 = 0x8765_4321;
+
+/// Constrains `commitment == sum(bases[i] * openings[i])`.
+fn assert_commitment<F: FieldExt>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    openings: &[Number<F>],
+    bases: &[F],
+    commitment: &Number<F>,
+) -> Result<(), Error> {
+    if openings.len() != bases.len() {
+        return Err(Error::Synthesis);
+    }
+
+    let terms = openings
+        .iter()
+        .zip(bases)
+        .map(|(opening, base)| {
+            chip.mul_by_constant(layouter.namespace(|| "base * opening"), opening.clone(), *base)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let sum = chip.accumulate(layouter.namespace(|| "sum of terms"), &terms)?;
+
+    chip.constrain_equal(layouter.namespace(|| "commitment == sum"), commitment, &sum)
+}
+
+/// Constrains `commitment` to open to `values`, each of which fits in `BITS`
+/// bits, under the blinding factor `blinding`.
+fn assert_confidential_commitment<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    values: &[Number<F>],
+    blinding: Number<F>,
+    commitment: &Number<F>,
+) -> Result<(), Error> {
+    for value in values {
+        chip.decompose::<BITS>(layouter.namespace(|| "range check value"), value.clone())?;
+    }
+
+    let openings = values.iter().cloned().chain([blinding]).collect::<Vec<_>>();
+    let bases = BASES.iter().chain([&BLINDING_BASE]).map(|base| F::from(*base)).collect::<Vec<_>>();
+    assert_commitment(chip, layouter.namespace(|| "commitment"), &openings, &bases, commitment)
+}
+
+/// Proves that the public commitment opens to private byte values.
+#[derive(Default)]
+#[allow(dead_code)]
+struct ConfidentialCircuit<F: FieldExt> {
+    values: Vec<Value<F>>,
+    blinding: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for ConfidentialCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+            blinding: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let values = self
+            .values
+            .iter()
+            .map(|value| chip.load_private(layouter.namespace(|| "load value"), *value))
+            .collect::<Result<Vec<_>, _>>()?;
+        let blinding = chip.load_private(layouter.namespace(|| "load blinding"), self.blinding)?;
+        let commitment = chip.load_instance(layouter.namespace(|| "load commitment"), 0)?;
+
+        assert_confidential_commitment::<F, 8>(
+            &chip,
+            layouter.namespace(|| "confidential commitment"),
+            &values,
+            blinding,
+            &commitment,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::{ConfidentialCircuit, BASES, BLINDING_BASE};
+
+    fn commit(values: &[u64], blinding: u64) -> Fp {
+        values
+            .iter()
+            .zip(BASES)
+            .fold(Fp::from(blinding) * Fp::from(BLINDING_BASE), |acc, (v, base)| {
+                acc + Fp::from(*v) * Fp::from(base)
+            })
+    }
+
+    #[test]
+    fn confidential_commitment() {
+        let k = 7;
+        let blinding = 987654321;
+        let circuit = |values: &[u64]| ConfidentialCircuit {
+            values: values.iter().map(|v| Value::known(Fp::from(*v))).collect(),
+            blinding: Value::known(Fp::from(blinding)),
+        };
+
+        for values in [[12, 200, 255], [0, 0, 0]] {
+            let commitment = commit(&values, blinding);
+            let prover = MockProver::run(k, &circuit(&values), vec![vec![commitment]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let commitment = commit(&[12, 200, 255], blinding);
+        let prover = MockProver::run(k, &circuit(&[12, 201, 255]), vec![vec![commitment]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // The commitment opens correctly, but 256 is not a byte.
+        let commitment = commit(&[12, 256, 255], blinding);
+        let prover = MockProver::run(k, &circuit(&[12, 256, 255]), vec![vec![commitment]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
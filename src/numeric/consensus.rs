@@ -0,0 +1,132 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Fixed validator weights.
+const WEIGHTS: [u64; 5] = [10, 20, 30, 15, 25];
+
+/// Constrains the validators flagged in `participation` to hold at least
+/// `num / den` of the total weight, and returns the participating weight.
+///
+/// The weights are fixed, so the total is a constant and the threshold check
+/// is `num * total <= den * participating`, which avoids dividing. Both sides
+/// must fit in `BITS` bits.
+fn assert_threshold<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    weights: &[u64],
+    participation: &[Number<F>],
+    num: u64,
+    den: u64,
+) -> Result<Number<F>, Error> {
+    if weights.len() != participation.len() {
+        return Err(Error::Synthesis);
+    }
+
+    let terms = weights
+        .iter()
+        .zip(participation)
+        .map(|(weight, flag)| {
+            chip.decompose::<1>(layouter.namespace(|| "flag is boolean"), flag.clone())?;
+            chip.mul_by_constant(layouter.namespace(|| "weight * flag"), flag.clone(), F::from(*weight))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let participating = chip.accumulate(layouter.namespace(|| "participating weight"), &terms)?;
+
+    let total = weights.iter().sum::<u64>();
+    let required = chip.load_constant(layouter.namespace(|| "num * total"), F::from(num * total))?;
+    let scaled = chip.mul_by_constant(
+        layouter.namespace(|| "den * participating"),
+        participating.clone(),
+        F::from(den),
+    )?;
+    chip.assert_less_equal::<BITS>(layouter.namespace(|| "threshold met"), required, scaled)?;
+
+    Ok(participating)
+}
+
+/// Proves that private participation flags reach a 2/3 weighted quorum and
+/// exposes the participating weight.
+#[derive(Default)]
+#[allow(dead_code)]
+struct ConsensusCircuit<F: FieldExt> {
+    participation: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for ConsensusCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            participation: vec![Value::unknown(); self.participation.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let participation = self
+            .participation
+            .iter()
+            .map(|flag| chip.load_private(layouter.namespace(|| "load flag"), *flag))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let weight = assert_threshold::<F, 16>(
+            &chip,
+            layouter.namespace(|| "2/3 quorum"),
+            &WEIGHTS,
+            &participation,
+            2,
+            3,
+        )?;
+
+        chip.expose_public(layouter.namespace(|| "expose weight"), weight, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::ConsensusCircuit;
+
+    #[test]
+    fn weighted_threshold() {
+        let k = 7;
+        let circuit = |participation: &[u64]| ConsensusCircuit {
+            participation: participation.iter().map(|p| Value::known(Fp::from(*p))).collect(),
+        };
+
+        // The total weight is 100, so a quorum needs a weight of at least 67.
+        let quorums = [([0, 1, 1, 1, 1], 90), ([0, 0, 1, 1, 1], 70), ([1, 1, 1, 1, 1], 100)];
+        for (participation, weight) in quorums {
+            let prover = MockProver::run(k, &circuit(&participation), vec![vec![Fp::from(weight)]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        for (participation, weight) in [([1, 0, 1, 0, 1], 65), ([1, 1, 1, 0, 0], 60)] {
+            let prover = MockProver::run(k, &circuit(&participation), vec![vec![Fp::from(weight)]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        // Participation flags must be boolean: 2 * 30 + 15 would otherwise count.
+        let prover = MockProver::run(k, &circuit(&[0, 0, 2, 1, 0]), vec![vec![Fp::from(75)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Returns the valid-mode convolution of `signal` with a constant `kernel`:
+/// output `i` is `sum_j signal[i + j] * kernel[m - 1 - j]` for a kernel of
+/// length `m`, so only windows lying fully inside the signal are kept.
+fn convolve<F: FieldExt>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    signal: &[Number<F>],
+    kernel: &[F],
+) -> Result<Vec<Number<F>>, Error> {
+    if kernel.is_empty() || kernel.len() > signal.len() {
+        return Err(Error::Synthesis);
+    }
+
+    signal
+        .windows(kernel.len())
+        .map(|window| {
+            let terms = window
+                .iter()
+                .zip(kernel.iter().rev())
+                .map(|(x, weight)| chip.mul_by_constant(layouter.namespace(|| "x * k"), x.clone(), *weight))
+                .collect::<Result<Vec<_>, _>>()?;
+            chip.accumulate(layouter.namespace(|| "window sum"), &terms)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct ConvolutionCircuit<F: FieldExt> {
+    signal: Vec<Value<F>>,
+    kernel: Vec<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for ConvolutionCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            signal: vec![Value::unknown(); self.signal.len()],
+            kernel: self.kernel.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let signal = self
+            .signal
+            .iter()
+            .map(|x| chip.load_private(layouter.namespace(|| "load signal"), *x))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let output = convolve(&chip, layouter.namespace(|| "convolve"), &signal, &self.kernel)?;
+        for (row, y) in output.into_iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| "expose output"), y, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::ConvolutionCircuit;
+
+    fn signed(value: i64) -> Fp {
+        if value < 0 {
+            -Fp::from(value.unsigned_abs())
+        } else {
+            Fp::from(value as u64)
+        }
+    }
+
+    /// Software reference for the valid-mode convolution.
+    fn reference(signal: &[i64], kernel: &[i64]) -> Vec<i64> {
+        signal
+            .windows(kernel.len())
+            .map(|window| window.iter().zip(kernel.iter().rev()).map(|(x, k)| x * k).sum())
+            .collect()
+    }
+
+    #[test]
+    fn convolve() {
+        let k = 6;
+        let kernel = [1, 0, -1];
+
+        let circuit = |signal: &[i64]| ConvolutionCircuit {
+            signal: signal.iter().map(|x| Value::known(signed(*x))).collect(),
+            kernel: kernel.map(signed).to_vec(),
+        };
+        let public = |output: &[i64]| vec![output.iter().copied().map(signed).collect()];
+
+        // A discrete derivative: [3 - 1, 4 - 2].
+        let signal = [1, 2, 3, 4];
+        let output = reference(&signal, &kernel);
+        assert_eq!(output, [2, 2]);
+        let prover = MockProver::run(k, &circuit(&signal), public(&output)).unwrap();
+        prover.assert_satisfied();
+
+        let signal = [5, -3, 0, 7, 2];
+        let prover = MockProver::run(k, &circuit(&signal), public(&reference(&signal, &kernel))).unwrap();
+        prover.assert_satisfied();
+
+        // Correlating instead of convolving flips the sign of every output.
+        let prover = MockProver::run(k, &circuit(&[1, 2, 3, 4]), public(&[-2, -2])).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
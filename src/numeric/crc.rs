@@ -0,0 +1,149 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::{
+    bitwise::{BitwiseChip, BitwiseConfig},
+    numeric::{FieldChip, NumericInstructions, Number},
+};
+
+/// The CRC-3 generator polynomial `x^3 + x + 1`.
+const GENERATOR: u64 = 0b1011;
+const DEGREE: usize = 3;
+
+/// Returns the CRC of `message`, the remainder of `message(x) * x^degree`
+/// divided by `generator(x)` over GF(2), as bits least significant first.
+/// `message` holds the coefficients highest degree first.
+///
+/// The division runs as a shift register: every message bit is XORed with the
+/// bit shifted out of the register, and the result is fed back into the taps
+/// of the generator. The XOR gates also check that the message is boolean.
+fn crc_remainder<F: FieldExt>(
+    chip: &BitwiseChip<F>,
+    field_chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    message: &[Number<F>],
+    generator: u64,
+    degree: usize,
+) -> Result<Vec<Number<F>>, Error> {
+    assert!(degree > 0 && generator >> degree == 1 && generator & 1 == 1);
+
+    let zero = field_chip.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+    let mut register = vec![zero; degree];
+
+    for bit in message {
+        let top = register[degree - 1].clone();
+        let feedback = chip.xor(layouter.namespace(|| "bit ^ r_top"), bit.clone(), top)?;
+
+        for i in (1..degree).rev() {
+            register[i] = if generator >> i & 1 == 1 {
+                let prev = register[i - 1].clone();
+                chip.xor(layouter.namespace(|| "r_i-1 ^ feedback"), prev, feedback.clone())?
+            } else {
+                register[i - 1].clone()
+            };
+        }
+        register[0] = feedback;
+    }
+
+    Ok(register)
+}
+
+/// Proves that the public CRC is the remainder of a private message.
+#[derive(Default)]
+#[allow(dead_code)]
+struct CrcCircuit<F: FieldExt> {
+    message: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for CrcCircuit<F> {
+    type Config = BitwiseConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            message: vec![Value::unknown(); self.message.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let field = FieldChip::configure(meta, advice, instance, constant);
+        BitwiseChip::configure(meta, field)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config.field.clone());
+        let chip = BitwiseChip::<F>::construct(config);
+
+        let message = self
+            .message
+            .iter()
+            .map(|bit| field_chip.load_private(layouter.namespace(|| "load bit"), *bit))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let remainder = crc_remainder(
+            &chip,
+            &field_chip,
+            layouter.namespace(|| "crc"),
+            &message,
+            GENERATOR,
+            DEGREE,
+        )?;
+        let crc = field_chip.compose(layouter.namespace(|| "compose crc"), &remainder)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose crc"), crc, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::{CrcCircuit, DEGREE, GENERATOR};
+
+    /// Software reference: polynomial long division of `message * x^DEGREE`.
+    fn crc(message: u64, len: usize) -> u64 {
+        let mut rem = message << DEGREE;
+        for shift in (0..len).rev() {
+            if rem >> (shift + DEGREE) & 1 == 1 {
+                rem ^= GENERATOR << shift;
+            }
+        }
+        rem
+    }
+
+    #[test]
+    fn crc_remainder() {
+        let k = 8;
+        let len = 14;
+        let circuit = |message: u64| CrcCircuit {
+            message: (0..len)
+                .rev()
+                .map(|i| Value::known(Fp::from(message >> i & 1)))
+                .collect(),
+        };
+
+        let message = 0b11010011101100;
+        assert_eq!(crc(message, len), 0b100);
+        for message in [message, 0b10000000000000, 0] {
+            let public_input = vec![vec![Fp::from(crc(message, len))]];
+            let prover = MockProver::run(k, &circuit(message), public_input).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // A single flipped bit changes the remainder.
+        let corrupted = message ^ 0b1000;
+        assert_ne!(crc(corrupted, len), 0b100);
+        let prover = MockProver::run(k, &circuit(corrupted), vec![vec![Fp::from(0b100)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
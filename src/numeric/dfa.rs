@@ -0,0 +1,240 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// A transition `(state, symbol, next state)`.
+type Transition = (u64, u64, u64);
+
+/// Strings over `a = 1` and `b = 2` that end in "ab". State 1 is the start,
+/// state 2 has just seen an `a` and state 3, the only accepting state, has just
+/// seen "ab".
+const TRANSITIONS: [Transition; 6] = [(1, 1, 2), (1, 2, 1), (2, 1, 2), (2, 2, 3), (3, 1, 2), (3, 2, 1)];
+const START: u64 = 1;
+const ACCEPTING: [u64; 1] = [3];
+
+/// Runs a deterministic finite automaton over witnessed symbols.
+///
+/// The transition function is a lookup table of `(state, symbol, next)` rows.
+/// Row `i` of the region holds state `i` and symbol `i`, and state `i + 1`
+/// sits on the next row, so each step is a single lookup. States and symbols
+/// are numbered from 1: the all-zero row the disabled lookups evaluate to is
+/// then not a transition any run can take.
+#[derive(Clone, Debug)]
+struct DfaConfig<F: FieldExt> {
+    field: FieldConfig<F>,
+    q_lookup: Selector,
+    state: TableColumn,
+    symbol: TableColumn,
+    next: TableColumn,
+}
+
+struct DfaChip<F: FieldExt> {
+    config: DfaConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> DfaChip<F> {
+    fn construct(config: DfaConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>, field: FieldConfig<F>) -> DfaConfig<F> {
+        let advice = field.advice;
+        let q_lookup = meta.complex_selector();
+        let state = meta.lookup_table_column();
+        let symbol = meta.lookup_table_column();
+        let next = meta.lookup_table_column();
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_lookup);
+            let current = meta.query_advice(advice[0], Rotation::cur());
+            let input = meta.query_advice(advice[1], Rotation::cur());
+            let successor = meta.query_advice(advice[0], Rotation::next());
+
+            vec![
+                (q.clone() * current, state),
+                (q.clone() * input, symbol),
+                (q * successor, next),
+            ]
+        });
+
+        DfaConfig {
+            field,
+            q_lookup,
+            state,
+            symbol,
+            next,
+        }
+    }
+
+    fn field_chip(&self) -> FieldChip<F> {
+        FieldChip::construct(self.config.field.clone())
+    }
+
+    fn load(&self, layouter: &mut impl Layouter<F>, transitions: &[Transition]) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load transition table",
+            |mut table| {
+                let rows = std::iter::once((0, 0, 0)).chain(transitions.iter().copied());
+
+                for (offset, (state, symbol, next)) in rows.enumerate() {
+                    let columns = [
+                        ("state", self.config.state, state),
+                        ("symbol", self.config.symbol, symbol),
+                        ("next", self.config.next, next),
+                    ];
+                    for (name, column, value) in columns {
+                        table.assign_cell(|| name, column, offset, || Value::known(F::from(value)))?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    /// Runs the automaton from `start` over `symbols` and returns the final state.
+    fn run(
+        &self,
+        mut layouter: impl Layouter<F>,
+        transitions: &[Transition],
+        start: u64,
+        symbols: &[Value<F>],
+    ) -> Result<Number<F>, Error> {
+        let advice = self.config.field.advice;
+        let step = |state: u64, symbol: u64| {
+            transitions
+                .iter()
+                .find(|(from, on, _)| *from == state && *on == symbol)
+                .map_or(0, |(_, _, next)| *next)
+        };
+
+        layouter.assign_region(
+            || "dfa run",
+            |mut region| {
+                let mut state = region
+                    .assign_advice_from_constant(|| "start", advice[0], 0, Assigned::from(F::from(start)))?;
+
+                for (row, symbol) in symbols.iter().enumerate() {
+                    self.config.q_lookup.enable(&mut region, row)?;
+
+                    region.assign_advice(|| "symbol", advice[1], row, || *symbol)?;
+                    let next = state.value_field().evaluate().zip(*symbol).map(|(state, symbol)| {
+                        let next = step(state.get_lower_128() as u64, symbol.get_lower_128() as u64);
+                        Assigned::from(F::from(next))
+                    });
+                    state = region.assign_advice(|| "state", advice[0], row + 1, || next)?;
+                }
+
+                Ok(Number(state))
+            },
+        )
+    }
+
+    /// Constrains the automaton to accept `symbols`.
+    fn assert_accepted(
+        &self,
+        mut layouter: impl Layouter<F>,
+        transitions: &[Transition],
+        start: u64,
+        accepting: &[u64],
+        symbols: &[Value<F>],
+    ) -> Result<(), Error> {
+        let field_chip = self.field_chip();
+        let state = self.run(layouter.namespace(|| "run"), transitions, start, symbols)?;
+
+        // The final state is accepting exactly when prod(state - accept_j) == 0.
+        let one = field_chip.load_constant(layouter.namespace(|| "one"), F::one())?;
+        let product = accepting.iter().try_fold(one, |product, accept| {
+            let diff = field_chip.add_constant(
+                layouter.namespace(|| "state - accept_j"),
+                state.clone(),
+                -F::from(*accept),
+            )?;
+            field_chip.mul(layouter.namespace(|| "product * diff"), product, diff)
+        })?;
+        let zero = field_chip.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        field_chip.constrain_equal(layouter.namespace(|| "accepted"), &product, &zero)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct DfaCircuit<F: FieldExt> {
+    symbols: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for DfaCircuit<F> {
+    type Config = DfaConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            symbols: vec![Value::unknown(); self.symbols.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let field = FieldChip::configure(meta, advice, instance, constant);
+        DfaChip::configure(meta, field)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = DfaChip::<F>::construct(config);
+
+        chip.load(&mut layouter, &TRANSITIONS)?;
+        chip.assert_accepted(
+            layouter.namespace(|| "match"),
+            &TRANSITIONS,
+            START,
+            &ACCEPTING,
+            &self.symbols,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::DfaCircuit;
+
+    #[test]
+    fn dfa() {
+        let k = 6;
+        let circuit = |input: &str| DfaCircuit {
+            symbols: input
+                .chars()
+                .map(|c| Value::known(Fp::from(c as u64 - 'a' as u64 + 1)))
+                .collect(),
+        };
+
+        for input in ["ab", "bbab", "abaab"] {
+            let prover = MockProver::run(k, &circuit(input), vec![vec![]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // "c" is not in the alphabet, so no transition matches it.
+        for input in ["", "aba", "abb", "acb"] {
+            let prover = MockProver::run(k, &circuit(input), vec![vec![]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
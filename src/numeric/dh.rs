@@ -0,0 +1,121 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::{
+    mod_reduce::ModReduceChip,
+    numeric::{FieldChip, FieldConfig, NumericInstructions, Number},
+};
+
+/// Returns `g^x mod p` for an exponent given as bits, least significant first,
+/// where `p` fits in `BITS` bits. Every product reduced is below `p^2`, so its
+/// quotient by `p` fits too.
+///
+/// The bits are consumed from the most significant end: every step squares the
+/// accumulator and, when the bit is set, multiplies it by `g`, reducing after
+/// each product.
+fn pow_mod<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    g: Number<F>,
+    x_bits: &[Number<F>],
+    p: Number<F>,
+) -> Result<Number<F>, Error> {
+    let mod_chip = ModReduceChip::construct(chip.config().clone());
+
+    let g = mod_chip.reduce_by::<BITS>(layouter.namespace(|| "g mod p"), g, p.clone())?;
+    let mut acc = chip.load_constant(layouter.namespace(|| "one"), F::one())?;
+    for bit in x_bits.iter().rev() {
+        chip.decompose::<1>(layouter.namespace(|| "bit is boolean"), bit.clone())?;
+
+        let squared = chip.square(layouter.namespace(|| "acc^2"), acc)?;
+        let squared = mod_chip.reduce_by::<BITS>(layouter.namespace(|| "acc^2 mod p"), squared, p.clone())?;
+        let multiplied = chip.mul(layouter.namespace(|| "acc^2 * g"), squared.clone(), g.clone())?;
+        let multiplied =
+            mod_chip.reduce_by::<BITS>(layouter.namespace(|| "acc^2 * g mod p"), multiplied, p.clone())?;
+
+        // acc = acc^2 + bit * (acc^2 * g - acc^2)
+        let diff = chip.sub(layouter.namespace(|| "acc^2 * g - acc^2"), multiplied, squared.clone())?;
+        let selected = chip.mul(layouter.namespace(|| "bit * diff"), bit.clone(), diff)?;
+        acc = chip.add(layouter.namespace(|| "acc^2 + bit * diff"), squared, selected)?;
+    }
+
+    Ok(acc)
+}
+
+/// Proves knowledge of a private exponent `x` such that `g^x mod p == y`, for a
+/// public generator `g`, modulus `p` and result `y` in rows 0, 1 and 2.
+#[derive(Default)]
+#[allow(dead_code)]
+struct DhCircuit<F: FieldExt> {
+    x_bits: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for DhCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            x_bits: vec![Value::unknown(); self.x_bits.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let g = chip.load_instance(layouter.namespace(|| "load g"), 0)?;
+        let p = chip.load_instance(layouter.namespace(|| "load p"), 1)?;
+        let x_bits = self
+            .x_bits
+            .iter()
+            .map(|bit| chip.load_private(layouter.namespace(|| "load exponent bit"), *bit))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let y = pow_mod::<F, 5>(&chip, layouter.namespace(|| "g^x mod p"), g, &x_bits, p)?;
+        chip.expose_public(layouter.namespace(|| "expose y"), y, 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::DhCircuit;
+
+    #[test]
+    fn discrete_log_knowledge() {
+        let k = 9;
+        let circuit = |x: u64| DhCircuit {
+            x_bits: (0..4).map(|i| Value::known(Fp::from(x >> i & 1))).collect(),
+        };
+        let public = |y: u64| vec![vec![Fp::from(5), Fp::from(23), Fp::from(y)]];
+
+        // 5^6 = 15625 = 679 * 23 + 8.
+        let prover = MockProver::run(k, &circuit(6), public(8)).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(0), public(1)).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(6), public(9)).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 5^7 = 17 mod 23, so 7 is not a discrete log of 8.
+        let prover = MockProver::run(k, &circuit(7), public(8)).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,84 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions};
+
+/// An evaluation proof in the style of a polynomial commitment opening, minus
+/// the commitment: the polynomial `p` is fixed by the circuit, and the prover
+/// shows `p(z) == y` for the public point `z` (instance row 0) and public
+/// value `y` (instance row 1).
+#[derive(Default)]
+#[allow(dead_code)]
+struct EvalProofCircuit<F: FieldExt> {
+    coeffs: Vec<F>,
+    z: Value<F>,
+    y: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for EvalProofCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            coeffs: self.coeffs.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let z = chip.load_private(layouter.namespace(|| "load z"), self.z)?;
+        let y = chip.load_private(layouter.namespace(|| "load y"), self.y)?;
+
+        let p_z = chip.eval_poly(layouter.namespace(|| "p(z)"), &self.coeffs, z.clone())?;
+        chip.constrain_equal(layouter.namespace(|| "p(z) == y"), &p_z, &y)?;
+
+        chip.expose_public(layouter.namespace(|| "expose z"), z, 0)?;
+        chip.expose_public(layouter.namespace(|| "expose y"), y, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::EvalProofCircuit;
+
+    #[test]
+    fn eval_proof() {
+        let k = 5;
+
+        // p(x) = 3 + 2x + x^2, so p(5) = 38.
+        let coeffs = vec![Fp::from(3), Fp::from(2), Fp::from(1)];
+        let (z, y) = (Fp::from(5), Fp::from(38));
+
+        let circuit = |y: Fp| EvalProofCircuit {
+            coeffs: coeffs.clone(),
+            z: Value::known(z),
+            y: Value::known(y),
+        };
+
+        let prover = MockProver::run(k, &circuit(y), vec![vec![z, y]]).unwrap();
+        prover.assert_satisfied();
+
+        let wrong_y = y + Fp::one();
+        let prover = MockProver::run(k, &circuit(wrong_y), vec![vec![z, wrong_y]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,152 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// An arithmetic expression over constants and indexed inputs.
+#[derive(Clone, Debug)]
+#[allow(dead_code)]
+enum Expr<F> {
+    Const(F),
+    /// The private input at this index.
+    PrivateInput(usize),
+    /// The public input at this index, read from instance row `index + 1`.
+    PublicInput(usize),
+    Add(Box<Expr<F>>, Box<Expr<F>>),
+    Mul(Box<Expr<F>>, Box<Expr<F>>),
+}
+
+#[allow(dead_code)]
+impl<F> Expr<F> {
+    fn add(lhs: Self, rhs: Self) -> Self {
+        Self::Add(Box::new(lhs), Box::new(rhs))
+    }
+
+    fn mul(lhs: Self, rhs: Self) -> Self {
+        Self::Mul(Box::new(lhs), Box::new(rhs))
+    }
+}
+
+/// Synthesizes `expr` bottom up, one chip instruction per node. Every use of a
+/// private input refers to the same loaded cell.
+fn synthesize_expr<F: FieldExt, L: Layouter<F>>(
+    chip: &FieldChip<F>,
+    layouter: &mut L,
+    expr: &Expr<F>,
+    private: &[Number<F>],
+) -> Result<Number<F>, Error> {
+    match expr {
+        Expr::Const(constant) => chip.load_constant(layouter.namespace(|| "constant"), *constant),
+        Expr::PrivateInput(index) => private.get(*index).cloned().ok_or(Error::Synthesis),
+        Expr::PublicInput(index) => chip.load_instance(layouter.namespace(|| "public input"), index + 1),
+        Expr::Add(lhs, rhs) => {
+            let lhs = synthesize_expr(chip, layouter, lhs, private)?;
+            let rhs = synthesize_expr(chip, layouter, rhs, private)?;
+            chip.add(layouter.namespace(|| "lhs + rhs"), lhs, rhs)
+        }
+        Expr::Mul(lhs, rhs) => {
+            let lhs = synthesize_expr(chip, layouter, lhs, private)?;
+            let rhs = synthesize_expr(chip, layouter, rhs, private)?;
+            chip.mul(layouter.namespace(|| "lhs * rhs"), lhs, rhs)
+        }
+    }
+}
+
+/// Proves that `expr` evaluates to the public output in instance row 0, for the
+/// private inputs and the public inputs in the rows after it.
+#[derive(Clone, Debug)]
+#[allow(dead_code)]
+struct MyCircuit<F: FieldExt> {
+    expr: Expr<F>,
+    private: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Default for MyCircuit<F> {
+    fn default() -> Self {
+        Self {
+            expr: Expr::Const(F::zero()),
+            private: vec![],
+        }
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            expr: self.expr.clone(),
+            private: vec![Value::unknown(); self.private.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let private = self
+            .private
+            .iter()
+            .map(|value| chip.load_private(layouter.namespace(|| "load private input"), *value))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let output = synthesize_expr(&chip, &mut layouter, &self.expr, &private)?;
+        chip.expose_public(layouter.namespace(|| "expose output"), output, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::{Expr, MyCircuit};
+
+    #[test]
+    fn expr_eval() {
+        let k = 5;
+        let a = || Expr::PrivateInput(0);
+        let b = || Expr::PrivateInput(1);
+        let circuit = |expr: Expr<Fp>| MyCircuit {
+            expr,
+            private: vec![Value::known(Fp::from(2)), Value::known(Fp::from(3))],
+        };
+        let run = |expr: Expr<Fp>, public: &[u64]| {
+            MockProver::run(k, &circuit(expr), vec![public.iter().copied().map(Fp::from).collect()]).unwrap()
+        };
+
+        // The static demo: c * a^2 * b^2 with c = 7.
+        let ab = Expr::mul(a(), b());
+        let demo = Expr::mul(Expr::Const(Fp::from(7)), Expr::mul(ab.clone(), ab));
+        run(demo.clone(), &[252]).assert_satisfied();
+        assert!(run(demo, &[253]).verify().is_err());
+
+        // (a + b) * x + 1 for a public x = 4.
+        let affine = Expr::add(Expr::mul(Expr::add(a(), b()), Expr::PublicInput(0)), Expr::Const(Fp::one()));
+        run(affine.clone(), &[21, 4]).assert_satisfied();
+        assert!(run(affine.clone(), &[21, 5]).verify().is_err());
+        assert!(run(affine, &[26, 4]).verify().is_err());
+
+        // A constant, and a deep left spine a * a * ... * a.
+        run(Expr::Const(Fp::from(9)), &[9]).assert_satisfied();
+        let power = (1..6).fold(a(), |acc, _| Expr::mul(acc, a()));
+        run(power, &[64]).assert_satisfied();
+
+        // Referring to a private input that was never given fails synthesis.
+        let missing = circuit(Expr::PrivateInput(2));
+        assert!(MockProver::run(k, &missing, vec![vec![Fp::zero()]]).is_err());
+    }
+}
@@ -0,0 +1,62 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions};
+
+/// Proves `n!` and exposes it.
+#[derive(Default)]
+#[allow(dead_code)]
+struct FactorialCircuit {
+    n: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for FactorialCircuit {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self { n: self.n }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let n_factorial = chip.factorial(layouter.namespace(|| "factorial"), self.n)?;
+
+        chip.expose_public(layouter.namespace(|| "expose n!"), n_factorial, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::FactorialCircuit;
+
+    #[test]
+    fn factorial() {
+        let k = 6;
+
+        for (n, n_factorial) in [(0, 1), (1, 1), (5, 120), (10, 3628800)] {
+            let public_input = vec![vec![Fp::from(n_factorial)]];
+            let prover = MockProver::run(k, &FactorialCircuit { n }, public_input).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let prover = MockProver::run(k, &FactorialCircuit { n: 5 }, vec![vec![Fp::from(24)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Lowest set bit of `i`, the size of the range covered by Fenwick node `i`.
+fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+/// Constrains `tree` to be the Fenwick tree of `values`: with 1-based indices,
+/// node `i` holds the sum of `values[i - lowbit(i) + 1..=i]`. `tree[0]` is node 1.
+fn assert_fenwick_tree<F: FieldExt>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    values: &[Number<F>],
+    tree: &[Number<F>],
+) -> Result<(), Error> {
+    if values.len() != tree.len() {
+        return Err(Error::Synthesis);
+    }
+
+    for (node, claimed) in tree.iter().enumerate().map(|(i, node)| (i + 1, node)) {
+        let covered = &values[node - lowbit(node)..node];
+        let sum = chip.accumulate(layouter.namespace(|| "node range sum"), covered)?;
+        chip.constrain_equal(layouter.namespace(|| "node == range sum"), claimed, &sum)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the sum of the first `position` values by adding the Fenwick nodes
+/// `position`, `position - lowbit(position)`, ... down to zero.
+fn fenwick_prefix_sum<F: FieldExt>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    tree: &[Number<F>],
+    position: usize,
+) -> Result<Number<F>, Error> {
+    if position > tree.len() {
+        return Err(Error::Synthesis);
+    }
+
+    let nodes = std::iter::successors(Some(position), |&node| Some(node - lowbit(node)))
+        .take_while(|&node| node > 0)
+        .map(|node| tree[node - 1].clone())
+        .collect::<Vec<_>>();
+
+    chip.accumulate(layouter.namespace(|| "sum of nodes"), &nodes)
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct FenwickCircuit<F: FieldExt> {
+    values: Vec<Value<F>>,
+    tree: Vec<Value<F>>,
+    position: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for FenwickCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+            tree: vec![Value::unknown(); self.tree.len()],
+            position: self.position,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let mut load = |values: &[Value<F>]| {
+            values
+                .iter()
+                .map(|value| chip.load_private(layouter.namespace(|| "load"), *value))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let values = load(&self.values)?;
+        let tree = load(&self.tree)?;
+
+        assert_fenwick_tree(&chip, layouter.namespace(|| "fenwick tree"), &values, &tree)?;
+        let sum = fenwick_prefix_sum(&chip, layouter.namespace(|| "prefix query"), &tree, self.position)?;
+
+        chip.expose_public(layouter.namespace(|| "expose prefix sum"), sum, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::{lowbit, FenwickCircuit};
+
+    fn build_tree(values: &[u64]) -> Vec<u64> {
+        (1..=values.len())
+            .map(|node| values[node - lowbit(node)..node].iter().sum())
+            .collect()
+    }
+
+    #[test]
+    fn fenwick_prefix_sum() {
+        let k = 7;
+        let values = [3, 1, 4, 1, 5, 9, 2, 6];
+        let known = |values: &[u64]| values.iter().map(|v| Value::known(Fp::from(*v))).collect();
+
+        let circuit = |tree: &[u64], position: usize| FenwickCircuit {
+            values: known(&values),
+            tree: known(tree),
+            position,
+        };
+
+        let tree = build_tree(&values);
+        for position in 0..=values.len() {
+            let sum = values[..position].iter().sum::<u64>();
+            let prover = MockProver::run(k, &circuit(&tree, position), vec![vec![Fp::from(sum)]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let prover = MockProver::run(k, &circuit(&tree, 5), vec![vec![Fp::from(15)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // A tampered node makes the tree inconsistent with the values.
+        let mut tampered = tree.clone();
+        tampered[3] += 1;
+        let prover = MockProver::run(k, &circuit(&tampered, 5), vec![vec![Fp::from(15)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Bucket `i` holds the values in `[EDGES[i], EDGES[i + 1])`.
+const EDGES: [u64; 4] = [0, 10, 20, 30];
+
+/// Places the `BITS`-bit `value` in one of the buckets delimited by the sorted
+/// constant `edges` and returns `counts` with that bucket's counter incremented.
+///
+/// With `below_j = value < edges[j]`, which is 0 up to the first edge above the
+/// value and 1 from there on, the bucket flags are `below_{i+1} - below_i`. They
+/// sum to `below_last - below_first`, which must be one: the value lies in
+/// exactly one bucket, and every other counter holds.
+fn histogram_update<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    value: Number<F>,
+    edges: &[F],
+    counts: &[Number<F>],
+) -> Result<Vec<Number<F>>, Error> {
+    if edges.len() != counts.len() + 1 {
+        return Err(Error::Synthesis);
+    }
+
+    let below = edges
+        .iter()
+        .map(|edge| {
+            let edge = chip.load_constant(layouter.namespace(|| "edge"), *edge)?;
+            chip.less_than::<BITS>(layouter.namespace(|| "value < edge"), value.clone(), edge)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (first, last) = (below[0].clone(), below[below.len() - 1].clone());
+    let in_range = chip.sub(layouter.namespace(|| "below_last - below_first"), last, first)?;
+    let one = chip.load_constant(layouter.namespace(|| "one"), F::one())?;
+    chip.constrain_equal(layouter.namespace(|| "value in some bucket"), &in_range, &one)?;
+
+    below
+        .windows(2)
+        .zip(counts)
+        .map(|(pair, count)| {
+            let flag = chip.sub(layouter.namespace(|| "bucket flag"), pair[1].clone(), pair[0].clone())?;
+            chip.add(layouter.namespace(|| "count + flag"), count.clone(), flag)
+        })
+        .collect()
+}
+
+/// Streams private values into the histogram and exposes the final counts.
+#[derive(Default)]
+#[allow(dead_code)]
+struct HistogramCircuit<F: FieldExt> {
+    values: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for HistogramCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            values: vec![Value::unknown(); self.values.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+        let edges = EDGES.map(F::from);
+
+        let mut counts = (1..EDGES.len())
+            .map(|_| chip.load_constant(layouter.namespace(|| "empty bucket"), F::zero()))
+            .collect::<Result<Vec<_>, _>>()?;
+        for value in &self.values {
+            let value = chip.load_private(layouter.namespace(|| "load value"), *value)?;
+            let update = layouter.namespace(|| "update");
+            counts = histogram_update::<F, 6>(&chip, update, value, &edges, &counts)?;
+        }
+
+        for (row, count) in counts.into_iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| "expose count"), count, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::HistogramCircuit;
+
+    #[test]
+    fn histogram() {
+        let k = 10;
+        let circuit = |values: &[u64]| HistogramCircuit {
+            values: values.iter().map(|v| Value::known(Fp::from(*v))).collect(),
+        };
+        let counts = |counts: [u64; 3]| vec![counts.map(Fp::from).to_vec()];
+
+        // Bucket edges fall on 10 and 20: 9 and 10 land in different buckets.
+        let values = [3, 15, 12, 29, 0, 9, 10];
+        let prover = MockProver::run(k, &circuit(&values), counts([3, 3, 1])).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(&values), counts([2, 4, 1])).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 30 lies past the last bucket, so no counter can take it.
+        for claimed in [[0, 0, 1], [0, 0, 0]] {
+            let prover = MockProver::run(k, &circuit(&[30]), counts(claimed)).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
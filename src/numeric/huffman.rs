@@ -0,0 +1,293 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error, Expression, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// A codeword `(code, length, symbol)`, with the code's bits read most
+/// significant first.
+type Codeword = (u64, usize, u64);
+
+/// A prefix-free code over symbols 1 to 3. No codeword starts with "111", which
+/// is therefore an invalid prefix.
+const CODEBOOK: [Codeword; 3] = [(0b0, 1, 1), (0b10, 2, 2), (0b110, 3, 3)];
+/// Length of the longest codeword.
+const MAX_LEN: usize = 3;
+
+/// Decodes the first symbol of a bit stream under a fixed prefix-free code.
+///
+/// The first `MAX_LEN` bits of the stream form a window. Since no codeword is a
+/// prefix of another, the window alone determines the symbol and how many bits
+/// it consumes, so the table holds a `(window, length, symbol)` row for every
+/// window that starts with a codeword. Both the lookup and the table offset
+/// the window by one, so the all-zero row the disabled lookups evaluate to
+/// matches no decode, not even the all-zero window.
+#[derive(Clone, Debug)]
+struct HuffmanConfig<F: FieldExt> {
+    field: FieldConfig<F>,
+    q_lookup: Selector,
+    window: TableColumn,
+    length: TableColumn,
+    symbol: TableColumn,
+}
+
+struct HuffmanChip<F: FieldExt> {
+    config: HuffmanConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> HuffmanChip<F> {
+    fn construct(config: HuffmanConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>, field: FieldConfig<F>) -> HuffmanConfig<F> {
+        let advice = field.advice;
+        let q_lookup = meta.complex_selector();
+        let window = meta.lookup_table_column();
+        let length = meta.lookup_table_column();
+        let symbol = meta.lookup_table_column();
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_lookup);
+            let bits = meta.query_advice(advice[0], Rotation::cur());
+            let one = Expression::Constant(F::one());
+            let consumed = meta.query_advice(advice[1], Rotation::cur());
+            let decoded = meta.query_advice(advice[0], Rotation::next());
+
+            vec![
+                (q.clone() * (bits + one), window),
+                (q.clone() * consumed, length),
+                (q * decoded, symbol),
+            ]
+        });
+
+        HuffmanConfig {
+            field,
+            q_lookup,
+            window,
+            length,
+            symbol,
+        }
+    }
+
+    fn field_chip(&self) -> FieldChip<F> {
+        FieldChip::construct(self.config.field.clone())
+    }
+
+    fn load(&self, layouter: &mut impl Layouter<F>, codebook: &[Codeword]) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load code table",
+            |mut table| {
+                let windows = codebook.iter().flat_map(|&(code, length, symbol)| {
+                    let free = MAX_LEN - length;
+                    (0..1u64 << free).map(move |suffix| ((code << free | suffix) + 1, length as u64, symbol))
+                });
+                let rows = std::iter::once((0, 0, 0)).chain(windows);
+
+                for (offset, (window, length, symbol)) in rows.enumerate() {
+                    let columns = [
+                        ("window", self.config.window, window),
+                        ("length", self.config.length, length),
+                        ("symbol", self.config.symbol, symbol),
+                    ];
+                    for (name, column, value) in columns {
+                        table.assign_cell(|| name, column, offset, || Value::known(F::from(value)))?;
+                    }
+                }
+
+                Ok(())
+            },
+        )
+    }
+
+    /// Decodes the first symbol of `bits`, returning it together with the number
+    /// of bits its codeword consumes.
+    fn decode_first(
+        &self,
+        mut layouter: impl Layouter<F>,
+        codebook: &[Codeword],
+        bits: &[Number<F>],
+    ) -> Result<(Number<F>, Number<F>), Error> {
+        let field_chip = self.field_chip();
+
+        let window_bits = bits.get(..MAX_LEN).ok_or(Error::Synthesis)?;
+        for bit in window_bits {
+            field_chip.decompose::<1>(layouter.namespace(|| "bit is boolean"), bit.clone())?;
+        }
+        // Compose takes bits least significant first, and the stream is read
+        // most significant first.
+        let lsb_first = window_bits.iter().rev().cloned().collect::<Vec<_>>();
+        let window = field_chip.compose(layouter.namespace(|| "window"), &lsb_first)?;
+
+        let decoded = window.evaluate().as_ref().map(|window| {
+            let window = window.get_lower_128() as u64;
+            codebook
+                .iter()
+                .find(|(code, length, _)| window >> (MAX_LEN - length) == *code)
+                .map_or((0, 0), |(_, length, symbol)| (*length as u64, *symbol))
+        });
+
+        self.assign_decode(layouter.namespace(|| "look up window"), &window, decoded)
+    }
+
+    /// Assigns the claimed `(length, symbol)` decode of `window` under the
+    /// lookup, returning the symbol and the length.
+    fn assign_decode(
+        &self,
+        mut layouter: impl Layouter<F>,
+        window: &Number<F>,
+        decoded: Value<(u64, u64)>,
+    ) -> Result<(Number<F>, Number<F>), Error> {
+        let advice = self.config.field.advice;
+
+        layouter.assign_region(
+            || "decode",
+            |mut region| {
+                self.config.q_lookup.enable(&mut region, 0)?;
+
+                window.0.copy_advice(|| "window", &mut region, advice[0], 0)?;
+                let length = region.assign_advice(
+                    || "length",
+                    advice[1],
+                    0,
+                    || decoded.map(|(length, _)| Assigned::from(F::from(length))),
+                )?;
+                let symbol = region.assign_advice(
+                    || "symbol",
+                    advice[0],
+                    1,
+                    || decoded.map(|(_, symbol)| Assigned::from(F::from(symbol))),
+                )?;
+
+                Ok((Number(symbol), Number(length)))
+            },
+        )
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct HuffmanCircuit<F: FieldExt> {
+    bits: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for HuffmanCircuit<F> {
+    type Config = HuffmanConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            bits: vec![Value::unknown(); self.bits.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let field = FieldChip::configure(meta, advice, instance, constant);
+        HuffmanChip::configure(meta, field)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = HuffmanChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        chip.load(&mut layouter, &CODEBOOK)?;
+
+        let bits = self
+            .bits
+            .iter()
+            .map(|bit| field_chip.load_private(layouter.namespace(|| "load bit"), *bit))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (symbol, length) = chip.decode_first(layouter.namespace(|| "decode"), &CODEBOOK, &bits)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose symbol"), symbol, 0)?;
+        field_chip.expose_public(layouter.namespace(|| "expose length"), length, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    use super::{HuffmanChip, HuffmanCircuit, HuffmanConfig, NumericInstructions, CODEBOOK};
+
+    /// Claims an arbitrary `(length, symbol)` decode of the all-zero window.
+    #[derive(Default)]
+    struct ForgedDecodeCircuit {
+        decoded: (u64, u64),
+    }
+
+    impl Circuit<Fp> for ForgedDecodeCircuit {
+        type Config = HuffmanConfig<Fp>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            HuffmanCircuit::configure(meta)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let chip = HuffmanChip::<Fp>::construct(config);
+            let field_chip = chip.field_chip();
+
+            chip.load(&mut layouter, &CODEBOOK)?;
+            let window = field_chip.load_constant(layouter.namespace(|| "window 000"), Fp::zero())?;
+            let decoded = Value::known(self.decoded);
+            let (symbol, length) = chip.assign_decode(layouter.namespace(|| "decode"), &window, decoded)?;
+
+            field_chip.expose_public(layouter.namespace(|| "expose symbol"), symbol, 0)?;
+            field_chip.expose_public(layouter.namespace(|| "expose length"), length, 1)
+        }
+    }
+
+    #[test]
+    fn decode_first() {
+        let k = 5;
+        let circuit = |stream: &str| HuffmanCircuit {
+            bits: stream
+                .chars()
+                .map(|c| Value::known(Fp::from(c.to_digit(2).unwrap() as u64)))
+                .collect(),
+        };
+        let public = |symbol: u64, length: u64| vec![vec![Fp::from(symbol), Fp::from(length)]];
+
+        for (stream, symbol, length) in [("1100", 3, 3), ("1011", 2, 2), ("0110", 1, 1), ("000", 1, 1)] {
+            let prover = MockProver::run(k, &circuit(stream), public(symbol, length)).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // The symbol decodes, but its codeword is claimed to consume too many bits.
+        let prover = MockProver::run(k, &circuit("1011"), public(2, 3)).unwrap();
+        assert!(prover.verify().is_err());
+
+        // No codeword starts with "111".
+        let prover = MockProver::run(k, &circuit("1110"), public(0, 0)).unwrap();
+        assert!(prover.verify().is_err());
+
+        // "000" decodes to symbol 1 only; the table's padding row is no decode.
+        let forged = |length: u64, symbol: u64| ForgedDecodeCircuit { decoded: (length, symbol) };
+        let prover = MockProver::run(k, &forged(1, 1), public(1, 1)).unwrap();
+        prover.assert_satisfied();
+        let prover = MockProver::run(k, &forged(0, 0), public(0, 0)).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
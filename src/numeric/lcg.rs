@@ -0,0 +1,139 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::{
+    mod_reduce::ModReduceChip,
+    numeric::{FieldChip, FieldConfig, NumericInstructions, Number},
+};
+
+/// Steps a linear congruential generator `state' = (a * state + c) mod n`.
+struct LcgChip<F: FieldExt> {
+    config: FieldConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> LcgChip<F> {
+    fn construct(config: FieldConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn field_chip(&self) -> FieldChip<F> {
+        FieldChip::construct(self.config.clone())
+    }
+
+    /// Returns the next state, where `a * state + c` divided by `n` and `n`
+    /// itself fit in `BITS` bits.
+    fn lcg_step<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        state: Number<F>,
+        a: F,
+        c: F,
+        n: F,
+    ) -> Result<Number<F>, Error> {
+        let field_chip = self.field_chip();
+
+        let next = field_chip.mul_by_constant(layouter.namespace(|| "a * state"), state, a)?;
+        let next = field_chip.add_constant(layouter.namespace(|| "a * state + c"), next, c)?;
+
+        let mod_chip = ModReduceChip::construct(self.config.clone());
+        mod_chip.reduce::<BITS>(layouter.namespace(|| "mod n"), next, n)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct LcgCircuit<F: FieldExt> {
+    seed: Value<F>,
+    steps: usize,
+}
+
+impl<F: FieldExt> LcgCircuit<F> {
+    const A: u64 = 5;
+    const C: u64 = 3;
+    const N: u64 = 16;
+}
+
+impl<F: FieldExt> Circuit<F> for LcgCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            seed: Value::unknown(),
+            steps: self.steps,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = LcgChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        let mut state = field_chip.load_private(layouter.namespace(|| "load seed"), self.seed)?;
+        for row in 0..self.steps {
+            state = chip.lcg_step::<8>(
+                layouter.namespace(|| "lcg step"),
+                state,
+                F::from(Self::A),
+                F::from(Self::C),
+                F::from(Self::N),
+            )?;
+            field_chip.expose_public(layouter.namespace(|| "expose state"), state.clone(), row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::LcgCircuit;
+
+    #[test]
+    fn lcg_sequence() {
+        let k = 8;
+        let steps = 5;
+        let seed = 7;
+
+        let circuit = LcgCircuit {
+            seed: Value::known(Fp::from(seed)),
+            steps,
+        };
+
+        let lcg = |state: u64| (LcgCircuit::<Fp>::A * state + LcgCircuit::<Fp>::C) % LcgCircuit::<Fp>::N;
+        let mut states = Vec::with_capacity(steps);
+        let mut state = seed;
+        for _ in 0..steps {
+            state = lcg(state);
+            states.push(Fp::from(state));
+        }
+
+        let prover = MockProver::run(k, &circuit, vec![states.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        states[2] += Fp::one();
+        let prover = MockProver::run(k, &circuit, vec![states]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Constrains the `DIGITS` decimal digits of `value` to pass the Luhn check.
+///
+/// Counting from the check digit, the least significant, every second digit is
+/// doubled and reduced by 9 if the result exceeds 9. The sum of all resulting
+/// digits must be divisible by 10.
+fn assert_luhn<F: FieldExt, const DIGITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    value: Number<F>,
+) -> Result<(), Error> {
+    let digits = chip.decompose_base(layouter.namespace(|| "decimal digits"), value, DIGITS, 10)?;
+    let five = chip.load_constant(layouter.namespace(|| "five"), F::from(5))?;
+
+    let terms = digits
+        .into_iter()
+        .enumerate()
+        .map(|(i, digit)| {
+            if i % 2 == 0 {
+                return Ok(digit);
+            }
+
+            // 2d - 9 exceeds 9 exactly when d >= 5.
+            let below_five =
+                chip.less_than::<4>(layouter.namespace(|| "d < 5"), digit.clone(), five.clone())?;
+            let adjust = chip.mul_by_constant(layouter.namespace(|| "9 * (d < 5)"), below_five, F::from(9))?;
+            let adjust = chip.add_constant(layouter.namespace(|| "9 * (d >= 5)"), adjust, -F::from(9))?;
+            let doubled = chip.add(layouter.namespace(|| "2d"), digit.clone(), digit)?;
+            chip.add(layouter.namespace(|| "2d - 9 * (d >= 5)"), doubled, adjust)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let sum = chip.accumulate(layouter.namespace(|| "digit sum"), &terms)?;
+    let ten = chip.load_constant(layouter.namespace(|| "ten"), F::from(10))?;
+    chip.assert_divisible::<16>(layouter.namespace(|| "10 | sum"), sum, ten)
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct LuhnCircuit<F: FieldExt> {
+    value: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for LuhnCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+        assert_luhn::<F, 16>(&chip, layouter.namespace(|| "luhn"), value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::LuhnCircuit;
+
+    #[test]
+    fn luhn() {
+        let k = 10;
+        let circuit = |value: u64| LuhnCircuit {
+            value: Value::known(Fp::from(value)),
+        };
+
+        for value in [79927398713, 4111111111111111, 0] {
+            let prover = MockProver::run(k, &circuit(value), vec![vec![]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // A wrong check digit, and two swapped adjacent digits.
+        for value in [79927398710, 4111111111111121, 79927398731] {
+            let prover = MockProver::run(k, &circuit(value), vec![vec![]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+#[derive(Clone, Copy, Debug)]
+#[allow(dead_code)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+}
+
+/// `registers[output] = registers[inputs[0]] op registers[inputs[1]]`
+#[derive(Clone, Copy, Debug)]
+struct Instruction {
+    op: Op,
+    inputs: [usize; 2],
+    output: usize,
+}
+
+/// Runs a straight-line program over a register file of `Number`s. The first
+/// registers hold the private inputs; every instruction is proven with the
+/// matching `FieldChip` gate, and register `output` is exposed publicly.
+#[derive(Default)]
+#[allow(dead_code)]
+struct MiniVmCircuit<F: FieldExt> {
+    inputs: Vec<Value<F>>,
+    program: Vec<Instruction>,
+    output: usize,
+}
+
+impl<F: FieldExt> MiniVmCircuit<F> {
+    fn execute(
+        &self,
+        chip: &FieldChip<F>,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<Vec<Option<Number<F>>>, Error> {
+        let mut registers = self
+            .inputs
+            .iter()
+            .map(|input| chip.load_private(layouter.namespace(|| "load input"), *input).map(Some))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for instruction in &self.program {
+            let read = |index: usize| {
+                registers
+                    .get(index)
+                    .cloned()
+                    .flatten()
+                    .ok_or(Error::Synthesis)
+            };
+            let (a, b) = (read(instruction.inputs[0])?, read(instruction.inputs[1])?);
+
+            let layouter = layouter.namespace(|| format!("{:?}", instruction));
+            let result = match instruction.op {
+                Op::Add => chip.add(layouter, a, b)?,
+                Op::Sub => chip.sub(layouter, a, b)?,
+                Op::Mul => chip.mul(layouter, a, b)?,
+            };
+
+            if registers.len() <= instruction.output {
+                registers.resize(instruction.output + 1, None);
+            }
+            registers[instruction.output] = Some(result);
+        }
+
+        Ok(registers)
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for MiniVmCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            inputs: vec![Value::unknown(); self.inputs.len()],
+            program: self.program.clone(),
+            output: self.output,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let mut registers = self.execute(&chip, layouter.namespace(|| "execute"))?;
+        let output = registers
+            .get_mut(self.output)
+            .and_then(Option::take)
+            .ok_or(Error::Synthesis)?;
+
+        chip.expose_public(layouter.namespace(|| "expose output"), output, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::{Instruction, MiniVmCircuit, Op};
+
+    #[test]
+    fn mini_vm() {
+        let k = 4;
+
+        // (a + b) * c, computed as a * c + b * c.
+        let program = vec![
+            Instruction { op: Op::Mul, inputs: [0, 2], output: 3 },
+            Instruction { op: Op::Mul, inputs: [1, 2], output: 4 },
+            Instruction { op: Op::Add, inputs: [3, 4], output: 5 },
+        ];
+        let (a, b, c) = (Fp::from(2), Fp::from(3), Fp::from(4));
+
+        let circuit = MiniVmCircuit {
+            inputs: vec![Value::known(a), Value::known(b), Value::known(c)],
+            program,
+            output: 5,
+        };
+
+        let mut public_input = vec![(a + b) * c];
+
+        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        public_input[0] += Fp::one();
+        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Reading a register that was never written is a synthesis error.
+        let circuit = MiniVmCircuit { output: 6, ..circuit };
+        assert!(MockProver::run(k, &circuit, vec![public_input]).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Reduces values modulo a constant or witnessed modulus on the columns of a
+/// `FieldConfig`.
+///
+/// The quotient and remainder are witnessed and checked against
+/// `value == quotient * n + remainder`, with both range checked to `BITS` bits
+/// and the remainder shown to be `< n`.
+pub(super) struct ModReduceChip<F: FieldExt, const WIDTH: usize = 2> {
+    config: FieldConfig<F, WIDTH>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const WIDTH: usize> ModReduceChip<F, WIDTH> {
+    pub(super) fn construct(config: FieldConfig<F, WIDTH>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn field_chip(&self) -> FieldChip<F, WIDTH> {
+        FieldChip::construct(self.config.clone())
+    }
+
+    /// Returns `value mod n`, where `value / n` and `n` fit in `BITS` bits.
+    pub(super) fn reduce<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Number<F>,
+        n: F,
+    ) -> Result<Number<F>, Error> {
+        let modulus = self.field_chip().load_constant(layouter.namespace(|| "n"), n)?;
+        self.reduce_by::<BITS>(layouter, value, modulus)
+    }
+
+    /// Returns `value mod n` for a witnessed modulus `n`, where `value / n` and
+    /// `n` fit in `BITS` bits. The circuit is unsatisfiable if `n` is zero.
+    pub(super) fn reduce_by<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Number<F>,
+        n: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        let division = value.evaluate().as_ref().zip(n.evaluate().as_ref()).map(|(value, n)| {
+            let (value, n) = (value.get_lower_128(), n.get_lower_128());
+            value.checked_div(n).zip(value.checked_rem(n)).unwrap_or((0, 0))
+        });
+
+        let quotient = division.map(|(quotient, _)| F::from_u128(quotient));
+        let remainder = division.map(|(_, remainder)| F::from_u128(remainder));
+        self.assign_division::<BITS>(layouter, value, n, quotient, remainder)
+    }
+
+    /// Constrains `value == quotient * n + remainder` for a witnessed quotient
+    /// and remainder, and returns the remainder.
+    fn assign_division<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Number<F>,
+        n: Number<F>,
+        quotient: Value<F>,
+        remainder: Value<F>,
+    ) -> Result<Number<F>, Error> {
+        let field_chip = self.field_chip();
+
+        let quotient = field_chip.load_private(layouter.namespace(|| "load quotient"), quotient)?;
+        field_chip.decompose::<BITS>(layouter.namespace(|| "range check quotient"), quotient.clone())?;
+
+        // The comparison only holds for operands of BITS bits. Without the range
+        // check, remainder - n passes as a remainder next to quotient + 1.
+        let remainder = field_chip.load_private(layouter.namespace(|| "load remainder"), remainder)?;
+        field_chip.decompose::<BITS>(layouter.namespace(|| "range check remainder"), remainder.clone())?;
+        field_chip.assert_less_than::<BITS>(
+            layouter.namespace(|| "remainder < n"),
+            remainder.clone(),
+            n.clone(),
+        )?;
+
+        let product = field_chip.mul(layouter.namespace(|| "quotient * n"), quotient, n)?;
+        let sum = field_chip.add(
+            layouter.namespace(|| "quotient * n + remainder"),
+            product,
+            remainder.clone(),
+        )?;
+        field_chip.constrain_equal(layouter.namespace(|| "value == quotient * n + remainder"), &value, &sum)?;
+
+        Ok(remainder)
+    }
+
+    /// Returns `a^-1 mod n`, where `a * a^-1` divided by `n` and `n` itself fit
+    /// in `BITS` bits. The circuit is unsatisfiable if `a` and `n` are not coprime.
+    pub(super) fn mod_inverse<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        n: F,
+    ) -> Result<Number<F>, Error> {
+        let field_chip = self.field_chip();
+
+        let n_int = n.get_lower_128();
+        let a_inv = a.evaluate().as_ref().map(|a| {
+            invert_mod(a.get_lower_128(), n_int)
+                .map(F::from_u128)
+                .unwrap_or(F::zero())
+        });
+        let a_inv = field_chip.load_private(layouter.namespace(|| "load a^-1"), a_inv)?;
+        field_chip.decompose::<BITS>(layouter.namespace(|| "range check a^-1"), a_inv.clone())?;
+        let modulus = field_chip.load_constant(layouter.namespace(|| "n"), n)?;
+        field_chip.assert_less_than::<BITS>(layouter.namespace(|| "a^-1 < n"), a_inv.clone(), modulus)?;
+
+        let product = field_chip.mul(layouter.namespace(|| "a * a^-1"), a, a_inv.clone())?;
+        let product = self.reduce::<BITS>(layouter.namespace(|| "mod n"), product, n)?;
+        let one = field_chip.load_constant(layouter.namespace(|| "one"), F::one())?;
+        field_chip.constrain_equal(layouter.namespace(|| "a * a^-1 == 1"), &product, &one)?;
+
+        Ok(a_inv)
+    }
+}
+
+/// Returns the inverse of `a` modulo `n`, or `None` if they are not coprime or
+/// `n` is zero.
+pub(super) fn invert_mod(a: u128, n: u128) -> Option<u128> {
+    let (mut r0, mut r1) = (n as i128, a.checked_rem(n)? as i128);
+    let (mut t0, mut t1) = (0i128, 1i128);
+
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+
+    (r0 == 1).then(|| t0.rem_euclid(n as i128) as u128)
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct ModInverseCircuit<F: FieldExt> {
+    a: Value<F>,
+    n: u64,
+}
+
+impl<F: FieldExt> Circuit<F> for ModInverseCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            a: Value::unknown(),
+            n: self.n,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = ModReduceChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let a_inv = chip.mod_inverse::<8>(layouter.namespace(|| "a^-1 mod n"), a, F::from(self.n))?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose a^-1"), a_inv, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        dev::MockProver,
+        pasta::Fp,
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+
+    use super::{ModInverseCircuit, ModReduceChip};
+    use crate::numeric::numeric::{FieldChip, FieldConfig, NumericInstructions};
+
+    /// Reduces `value` mod `n` with a quotient and remainder of the test's choosing.
+    #[derive(Default)]
+    struct ForgedDivisionCircuit {
+        value: u64,
+        n: u64,
+        quotient: Fp,
+        remainder: Fp,
+    }
+
+    impl Circuit<Fp> for ForgedDivisionCircuit {
+        type Config = FieldConfig<Fp>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let advice = [meta.advice_column(), meta.advice_column()];
+            let instance = meta.instance_column();
+            let constant = meta.fixed_column();
+            FieldChip::configure(meta, advice, instance, constant)
+        }
+
+        fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let chip = ModReduceChip::<Fp>::construct(config);
+            let field_chip = chip.field_chip();
+
+            let value = Value::known(Fp::from(self.value));
+            let value = field_chip.load_private(layouter.namespace(|| "load value"), value)?;
+            let n = field_chip.load_constant(layouter.namespace(|| "n"), Fp::from(self.n))?;
+            chip.assign_division::<8>(
+                layouter.namespace(|| "value mod n"),
+                value,
+                n,
+                Value::known(self.quotient),
+                Value::known(self.remainder),
+            )?;
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reduce_rejects_negative_remainder() {
+        let k = 7;
+        let circuit = |quotient: u64, remainder: Fp| ForgedDivisionCircuit {
+            value: 69,
+            n: 26,
+            quotient: Fp::from(quotient),
+            remainder,
+        };
+
+        // 69 == 2 * 26 + 17.
+        let prover = MockProver::run(k, &circuit(2, Fp::from(17)), vec![vec![]]).unwrap();
+        prover.assert_satisfied();
+
+        // 69 == 3 * 26 - 9 as well, and 26 - (-9) - 1 fits in 8 bits.
+        let prover = MockProver::run(k, &circuit(3, -Fp::from(9)), vec![vec![]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn mod_inverse() {
+        let k = 7;
+        let circuit = |a: u64, n: u64| ModInverseCircuit {
+            a: Value::known(Fp::from(a)),
+            n,
+        };
+
+        let prover = MockProver::run(k, &circuit(3, 7), vec![vec![Fp::from(5)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(3, 7), vec![vec![Fp::from(4)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 4 and 6 share a factor, so 4 has no inverse mod 6.
+        let prover = MockProver::run(k, &circuit(4, 6), vec![vec![Fp::zero()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Constrains `timestamps` to be strictly increasing, with each timestamp of at
+/// most `BITS` bits, and returns the span `last - first`.
+fn assert_strictly_increasing<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    timestamps: &[Number<F>],
+) -> Result<Number<F>, Error> {
+    let (first, last) = match timestamps {
+        [first, .., last] => (first, last),
+        _ => return Err(Error::Synthesis),
+    };
+
+    for pair in timestamps.windows(2) {
+        chip.assert_less_than::<BITS>(
+            layouter.namespace(|| "previous < next"),
+            pair[0].clone(),
+            pair[1].clone(),
+        )?;
+    }
+
+    chip.sub(layouter.namespace(|| "last - first"), last.clone(), first.clone())
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct MonotonicCircuit<F: FieldExt> {
+    timestamps: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for MonotonicCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            timestamps: vec![Value::unknown(); self.timestamps.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let timestamps = self
+            .timestamps
+            .iter()
+            .map(|timestamp| chip.load_private(layouter.namespace(|| "load timestamp"), *timestamp))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let span = assert_strictly_increasing::<F, 32>(&chip, layouter.namespace(|| "ordering"), &timestamps)?;
+
+        chip.expose_public(layouter.namespace(|| "expose span"), span, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::MonotonicCircuit;
+
+    #[test]
+    fn strictly_increasing_timestamps() {
+        let k = 9;
+        let circuit = |timestamps: &[u64]| MonotonicCircuit {
+            timestamps: timestamps.iter().map(|t| Value::known(Fp::from(*t))).collect(),
+        };
+
+        let prover =
+            MockProver::run(k, &circuit(&[1000, 1005, 1300, 2000]), vec![vec![Fp::from(1000)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover =
+            MockProver::run(k, &circuit(&[1000, 1005, 1300, 2000]), vec![vec![Fp::from(999)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Repeated and decreasing timestamps are both rejected.
+        let prover =
+            MockProver::run(k, &circuit(&[1000, 1005, 1005, 2000]), vec![vec![Fp::from(1000)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let prover =
+            MockProver::run(k, &circuit(&[1000, 1300, 1005, 2000]), vec![vec![Fp::from(1000)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
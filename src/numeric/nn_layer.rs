@@ -0,0 +1,165 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Returns `max(x, 0)` for a signed `x` in `[-2^(BITS-1), 2^(BITS-1))`, with
+/// negative values represented as field negations.
+fn relu<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    x: Number<F>,
+) -> Result<Number<F>, Error> {
+    let sign = chip
+        .to_twos_complement::<BITS>(layouter.namespace(|| "two's complement"), x.clone())?
+        .pop()
+        .unwrap();
+
+    let one = chip.load_constant(layouter.namespace(|| "one"), F::one())?;
+    let non_negative = chip.sub(layouter.namespace(|| "1 - sign"), one, sign)?;
+    chip.mul(layouter.namespace(|| "x * (1 - sign)"), x, non_negative)
+}
+
+/// Evaluates a dense layer `relu(weights * input + biases)`, where row `i` of
+/// `weights` holds the weights of output `i`. Every pre-activation must fit in
+/// `BITS` bits as a signed value.
+fn relu_layer<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    input: &[Number<F>],
+    weights: &[Vec<F>],
+    biases: &[F],
+) -> Result<Vec<Number<F>>, Error> {
+    if weights.len() != biases.len() || weights.iter().any(|row| row.len() != input.len()) {
+        return Err(Error::Synthesis);
+    }
+
+    weights
+        .iter()
+        .zip(biases)
+        .map(|(row, bias)| {
+            let terms = row
+                .iter()
+                .zip(input)
+                .map(|(weight, x)| {
+                    chip.mul_by_constant(layouter.namespace(|| "w_j * x_j"), x.clone(), *weight)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let sum = chip.accumulate(layouter.namespace(|| "sum of terms"), &terms)?;
+            let pre_activation = chip.add_constant(layouter.namespace(|| "sum + bias"), sum, *bias)?;
+
+            relu::<F, BITS>(chip, layouter.namespace(|| "relu"), pre_activation)
+        })
+        .collect()
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct NnLayerCircuit<F: FieldExt> {
+    input: Vec<Value<F>>,
+    weights: Vec<Vec<F>>,
+    biases: Vec<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for NnLayerCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            input: vec![Value::unknown(); self.input.len()],
+            weights: self.weights.clone(),
+            biases: self.biases.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let input = self
+            .input
+            .iter()
+            .map(|x| chip.load_private(layouter.namespace(|| "load input"), *x))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let output = relu_layer::<F, 16>(
+            &chip,
+            layouter.namespace(|| "relu layer"),
+            &input,
+            &self.weights,
+            &self.biases,
+        )?;
+
+        for (row, y) in output.into_iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| "expose output"), y, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::NnLayerCircuit;
+
+    fn signed(value: i64) -> Fp {
+        if value < 0 {
+            -Fp::from(value.unsigned_abs())
+        } else {
+            Fp::from(value as u64)
+        }
+    }
+
+    /// Software reference for the layer.
+    fn reference(input: &[i64], weights: &[[i64; 2]], biases: &[i64]) -> Vec<i64> {
+        weights
+            .iter()
+            .zip(biases)
+            .map(|(row, bias)| (row.iter().zip(input).map(|(w, x)| w * x).sum::<i64>() + bias).max(0))
+            .collect()
+    }
+
+    #[test]
+    fn relu_layer() {
+        let k = 8;
+        let weights = [[2, -1], [-1, 1]];
+        let biases = [-4, 1];
+
+        let circuit = |input: &[i64]| NnLayerCircuit {
+            input: input.iter().map(|x| Value::known(signed(*x))).collect(),
+            weights: weights.iter().map(|row| row.map(signed).to_vec()).collect(),
+            biases: biases.map(signed).to_vec(),
+        };
+        let public = |output: &[i64]| vec![output.iter().copied().map(signed).collect()];
+
+        // [3, 5] gives pre-activations [-3, 3]; [7, -2] gives [12, -8].
+        for input in [[3, 5], [7, -2], [0, 0]] {
+            let output = reference(&input, &weights, &biases);
+            let prover = MockProver::run(k, &circuit(&input), public(&output)).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // Skipping the nonlinearity does not verify.
+        let prover = MockProver::run(k, &circuit(&[3, 5]), public(&[-3, 3])).unwrap();
+        assert!(prover.verify().is_err());
+
+        let prover = MockProver::run(k, &circuit(&[3, 5]), public(&[0, 4])).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
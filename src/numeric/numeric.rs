@@ -1,234 +1,4487 @@
 use std::marker::PhantomData;
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{AssignedCell, Chip, Layouter, SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Fixed, Instance, Selector},
+    circuit::{AssignedCell, Cell, Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{
+        Advice, Assigned, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector,
+    },
     poly::Rotation,
 };
 
-trait NumericInstructions<F: FieldExt>: Chip<F> {
+use crate::is_zero::is_zero_gadget::{IsZeroChip, IsZeroConfig};
+
+use super::mod_reduce::{invert_mod, ModReduceChip};
+
+pub trait NumericInstructions<F: FieldExt>: Chip<F> {
     type Num;
 
-    fn load_private(&self, layouter: impl Layouter<F>, value: Value<F>) -> Result<Self::Num, Error>;
+    fn load_private(&self, layouter: impl Layouter<F>, value: Value<F>) -> Result<Self::Num, Error>;
+
+    /// Loads `values` in a single region, filling every advice column of a row
+    /// before moving to the next.
+    fn load_privates(&self, layouter: impl Layouter<F>, values: &[Value<F>]) -> Result<Vec<Self::Num>, Error>;
+
+    fn load_constant(&self, layouter: impl Layouter<F>, constant: F) -> Result<Self::Num, Error>;
+
+    /// Loads the public input at `row` of the instance column.
+    fn load_instance(&self, layouter: impl Layouter<F>, row: usize) -> Result<Self::Num, Error>;
+
+    fn mul(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    fn sub(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    fn neg(&self, layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error>;
+
+    /// Returns `a * b + c` from a single gate.
+    fn mul_add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+        c: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `a` if `cond` is 1 and `b` if it is 0, constraining `cond` to be
+    /// boolean.
+    fn select(
+        &self,
+        layouter: impl Layouter<F>,
+        cond: Self::Num,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    fn mul_by_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        constant: F,
+    ) -> Result<Self::Num, Error>;
+
+    fn add_constant(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        constant: F,
+    ) -> Result<Self::Num, Error>;
+
+    /// Decomposes `value` into `BITS` boolean cells, least significant bit first.
+    fn decompose<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+    ) -> Result<Vec<Self::Num>, Error>;
+
+    /// Recomposes boolean cells, least significant bit first, into a single value.
+    fn compose(
+        &self,
+        layouter: impl Layouter<F>,
+        bits: &[Self::Num],
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `floor(value / 2^shift)` for a `value` that fits in `BITS` bits.
+    fn shift_right<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+        shift: usize,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns 1 if `a < b` and 0 otherwise, where both values fit in `BITS` bits.
+    fn less_than<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Constrains `a < b`, where both values fit in `BITS` bits.
+    fn assert_less_than<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<(), Error>;
+
+    /// Constrains `a <= b`, where both values fit in `BITS` bits.
+    fn assert_less_equal<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<(), Error>;
+
+    /// Returns `prev + alpha * (sample - prev)` with `alpha = alpha_num / alpha_den`,
+    /// rounded down. `alpha_den` must be a power of two and the weighted sum
+    /// `alpha_den * prev + alpha_num * (sample - prev)` must fit in `BITS` bits.
+    fn ema_update<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        prev: Self::Num,
+        sample: Self::Num,
+        alpha_num: u64,
+        alpha_den: u64,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns the sum of `xs`.
+    fn accumulate(
+        &self,
+        layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+    ) -> Result<Self::Num, Error>;
+
+    /// Constrains `sum(a) == sum(b)`.
+    fn assert_equal_sums(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &[Self::Num],
+        b: &[Self::Num],
+    ) -> Result<(), Error>;
+
+    /// Rounds a fixed-point `value` with `SCALE` fractional bits to the nearest
+    /// integer, with halves rounding up. `value` must fit in `BITS` bits.
+    fn round<const BITS: usize, const SCALE: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns the `BITS`-bit two's-complement pattern of a signed `value`, least
+    /// significant bit first. `value` must lie in `[-2^(BITS-1), 2^(BITS-1))`,
+    /// with negative values represented as field negations.
+    fn to_twos_complement<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+    ) -> Result<Vec<Self::Num>, Error>;
+
+    /// Constrains `divisor` to divide `value` using a private quotient. Every
+    /// nonzero divisor divides in the field, so the quotient is range checked
+    /// to `BITS` bits; `value` and `divisor` must fit in `BITS` bits as well.
+    fn assert_divisible<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+        divisor: Self::Num,
+    ) -> Result<(), Error>;
+
+    /// Evaluates the polynomial `sum coeffs[i] * x^i` at `x` with Horner's rule.
+    fn eval_poly(
+        &self,
+        layouter: impl Layouter<F>,
+        coeffs: &[F],
+        x: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Constrains `b` to be the transpose of `a`, i.e. `b[j][i] == a[i][j]`.
+    fn assert_transpose(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &[Vec<Self::Num>],
+        b: &[Vec<Self::Num>],
+    ) -> Result<(), Error>;
+
+    /// Returns the dot product `sum(a_i * b_i)` of two equal-length vectors.
+    fn dot(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &[Self::Num],
+        b: &[Self::Num],
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns the dot product of two equal-length vectors like [`Self::dot`],
+    /// but in a single region: a running sum in `advice[0]` alternates with the
+    /// rows holding `a_i` and `b_i`, and one gate per pair adds `a_i * b_i`.
+    fn inner_product(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &[Self::Num],
+        b: &[Self::Num],
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `floor(value / 2^K)` for a `value` that fits in `BITS` bits by
+    /// splitting off the low `K` bits and range checking the quotient to
+    /// `BITS - K` bits, rather than decomposing all of `value` like
+    /// [`Self::shift_right`].
+    fn floor_div_pow2<const BITS: usize, const K: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Constrains `xs` to be an arithmetic progression and returns its common
+    /// difference. `xs` must have at least two elements.
+    fn assert_arithmetic_progression(
+        &self,
+        layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+    ) -> Result<Self::Num, Error>;
+
+    /// Constrains `xs` to be a geometric progression and returns its common
+    /// ratio. `xs` must have at least two elements and a non-zero first element.
+    fn assert_geometric_progression(
+        &self,
+        layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns 1 if the discriminant `b^2 - 4ac` is a square in the field, so that
+    /// `ax^2 + bx + c` has roots in the field when `a != 0`, and 0 otherwise.
+    fn has_real_roots(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+        c: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns the integer square root `r = floor(sqrt(n))` and the remainder
+    /// `n - r^2`, where `n` fits in `BITS` bits.
+    fn isqrt<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        n: Self::Num,
+    ) -> Result<(Self::Num, Self::Num), Error>;
+
+    /// Returns 1 if `n` is a perfect square and 0 otherwise, keeping the root
+    /// private. `n` must fit in `BITS` bits.
+    fn assert_perfect_square<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        n: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns the toy hash `(a + 3b + 7)^5` of two values. The power map is a
+    /// permutation of the field, so the hash is easy to prove but has no
+    /// cryptographic strength; it stands in for a real hash in examples.
+    fn toy_hash(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `x^2`.
+    fn square(&self, layouter: impl Layouter<F>, x: Self::Num) -> Result<Self::Num, Error>;
+
+    /// Constrains `(sample - mean)^2 <= n^2 * variance`, i.e. `sample` lies within
+    /// `n` standard deviations of `mean`. `|sample - mean|` must fit in `BITS / 2`
+    /// bits, so that its field square is the integer square, and both sides must
+    /// fit in `BITS` bits.
+    fn assert_within_std_devs<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        sample: Self::Num,
+        mean: Self::Num,
+        variance: Self::Num,
+        n: u64,
+    ) -> Result<(), Error>;
+
+    /// Decomposes `value` into `BITS` bits and constrains them, least significant
+    /// first, to the public inputs at rows `instance_start..instance_start + BITS`.
+    fn assert_bits_equal_public<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+        instance_start: usize,
+    ) -> Result<(), Error>;
+
+    /// Returns `max(a - b, 0)`, where both values fit in `BITS` bits.
+    fn sub_saturating<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Constrains `value == sum(selected_i * 2^i)` with every `selected_i` boolean,
+    /// i.e. `selected` picks the distinct powers of two summing to `value`. At
+    /// most `BITS` selectors may be given.
+    fn assert_distinct_power_sum<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+        selected: &[Self::Num],
+    ) -> Result<(), Error>;
+
+    /// Evaluates at `x` the polynomial of least degree through `points`, given as
+    /// `(x_j, y_j)` pairs with distinct `x_j`, in Lagrange form.
+    fn lagrange_eval(
+        &self,
+        layouter: impl Layouter<F>,
+        points: &[(F, F)],
+        x: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns 1 if the `DIGITS` base-`base` digits of `value`, leading zeros
+    /// included, read the same in both directions, and 0 otherwise.
+    fn is_palindrome<const DIGITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+        base: u64,
+    ) -> Result<Self::Num, Error>;
+
+    /// Evaluates a piecewise-linear function of a `BITS`-bit `x`. Segment `i`
+    /// is the line `slopes[i] * x + intercepts[i]` and covers
+    /// `[breakpoints[i - 1], breakpoints[i])`, with the first and last segments
+    /// unbounded. `breakpoints` must be increasing.
+    fn piecewise_linear<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        x: Self::Num,
+        breakpoints: &[F],
+        slopes: &[F],
+        intercepts: &[F],
+    ) -> Result<Self::Num, Error>;
+
+    /// Constrains `gcd(a, n) == 1` by proving `a * x + n * y == 1` for witnessed
+    /// Bezout coefficients. `x` is taken in `[0, n)`, which makes `y = -q` for a
+    /// `BITS`-bit `q`; `a` and `n > 1` must fit in `BITS` bits as well.
+    fn assert_coprime<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        n: F,
+    ) -> Result<(), Error>;
+
+    /// Returns the larger of two `BITS`-bit values.
+    fn max<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns the prefix maxima of `BITS`-bit values: element `i` is the
+    /// maximum of `xs[..=i]`.
+    fn running_max<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+    ) -> Result<Vec<Self::Num>, Error>;
+
+    /// Returns `n!` as the product chain `1 * 2 * ... * n`, one multiplication by a
+    /// constant per factor.
+    fn factorial(&self, layouter: impl Layouter<F>, n: usize) -> Result<Self::Num, Error>;
+
+    /// Constrains `claimed == C(n, k)` by proving `claimed * k! * (n - k)! == n!`,
+    /// which avoids dividing. The factorials are nonzero in the field, so the
+    /// claim is unique.
+    fn assert_binomial(
+        &self,
+        layouter: impl Layouter<F>,
+        n: u64,
+        k: u64,
+        claimed: Self::Num,
+    ) -> Result<(), Error>;
+
+    /// Constrains every `(value, min, max)` entry to `min <= value < max`, where
+    /// all values and bounds fit in `BITS` bits.
+    fn validate_bounds<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        values: &[(Self::Num, F, F)],
+    ) -> Result<(), Error>;
+
+    /// Constrains `x * x == y (mod n)` for a witnessed square root `x`, where
+    /// `x * x / n`, `y / n` and `n` fit in `BITS` bits.
+    fn assert_mod_sqrt<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        y: Self::Num,
+        x: Self::Num,
+        n: F,
+    ) -> Result<(), Error>;
+
+    /// Constrains the fraction `num / den` to be in lowest terms, that is
+    /// `gcd(num, den) == 1`, with `num` and `den > 1` of at most `BITS` bits.
+    fn assert_lowest_terms<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        num: Self::Num,
+        den: Self::Num,
+    ) -> Result<(), Error>;
+
+    /// Constrains `claimed` to be the `k`-th smallest of `BITS`-bit values,
+    /// counting from zero: `claimed` is one of `xs` and exactly `k` of `xs` are
+    /// less than it.
+    fn assert_kth_smallest<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+        k: usize,
+        claimed: Self::Num,
+    ) -> Result<(), Error>;
+
+    /// Accumulates signed `deltas` into a running balance starting at zero and
+    /// returns the final balance. The balance must stay non-negative, and below
+    /// `2^(BITS-1)`, after every step.
+    fn assert_nonnegative_balance<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        deltas: &[Self::Num],
+    ) -> Result<Self::Num, Error>;
+
+    /// Packs at most `N` bytes into one value, the first byte most significant,
+    /// so `[0x41, 0x42]` packs to `0x4142`. Each byte is range checked to 8 bits.
+    fn pack_bytes<const N: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        bytes: &[Self::Num],
+    ) -> Result<Self::Num, Error>;
+
+    /// Compare-exchange: returns `(min(a, b), max(a, b))` for `BITS`-bit values.
+    fn sort_pair<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<(Self::Num, Self::Num), Error>;
+
+    /// Returns the sum of every `window` consecutive values, as differences of
+    /// prefix sums: window `i` is `prefix[i + window] - prefix[i]`.
+    fn windowed_sum(
+        &self,
+        layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+        window: usize,
+    ) -> Result<Vec<Self::Num>, Error>;
+
+    /// Constrains `digits`, most significant first, to have no leading zeros:
+    /// the first digit is nonzero unless every digit is zero.
+    fn assert_canonical(&self, layouter: impl Layouter<F>, digits: &[Self::Num]) -> Result<(), Error>;
+
+    /// Constrains the half-open intervals `[a_start, a_end)` and
+    /// `[b_start, b_end)` of `BITS`-bit bounds not to overlap: `a_end <= b_start`
+    /// or `b_end <= a_start`. Adjacent intervals do not overlap.
+    fn assert_no_overlap<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        a_start: Self::Num,
+        a_end: Self::Num,
+        b_start: Self::Num,
+        b_end: Self::Num,
+    ) -> Result<(), Error>;
+
+    /// Returns `base^exp` for a constant `exp`, by square-and-multiply over the
+    /// bits of the exponent on the `mul` gate.
+    fn pow_const(&self, layouter: impl Layouter<F>, base: Self::Num, exp: u64) -> Result<Self::Num, Error>;
+
+    /// Constrains `divisor` not to divide `value`, from a witnessed quotient and
+    /// a remainder in `[1, divisor)`, where all of them fit in `BITS` bits.
+    fn assert_not_divisible<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+        divisor: Self::Num,
+    ) -> Result<(), Error>;
+
+    /// Constrains the prime `p` to appear in `n` with exponent exactly `e`:
+    /// `p^e` divides `n` but `p^(e+1)` does not. `n` fits in `BITS` bits.
+    fn assert_prime_power_exponent<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        n: Self::Num,
+        p: F,
+        e: u64,
+    ) -> Result<(), Error>;
+
+    /// Constrains `value` to be one of the constants in `set`, by requiring the
+    /// product of `value - s` over the set to vanish.
+    fn assert_one_of(&self, layouter: impl Layouter<F>, value: Self::Num, set: &[F]) -> Result<(), Error>;
+
+    /// Returns the `DIGITS` balanced-ternary digits of `value`, least significant
+    /// first. Each digit is one of `-1`, `0` and `1`, so `value` must lie in
+    /// `[-(3^DIGITS - 1) / 2, (3^DIGITS - 1) / 2]`.
+    fn to_balanced_ternary<const DIGITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+    ) -> Result<Vec<Self::Num>, Error>;
+
+    /// Returns a fixed-point reciprocal `r` of `x` with `SCALE` fractional bits,
+    /// within one unit of `2^SCALE / x`: `|x * r - 2^SCALE| < x`. `r`, `2 * x` and
+    /// `x * r + x` must fit in `BITS` bits.
+    fn reciprocal<const BITS: usize, const SCALE: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        x: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `a / b` from a witnessed inverse, constraining `b * b_inv == 1` and
+    /// `out == a * b_inv`. The circuit is unsatisfiable if `b` is zero.
+    fn div(&self, layouter: impl Layouter<F>, a: Self::Num, b: Self::Num) -> Result<Self::Num, Error>;
+
+    /// Constrains `a == b` with a copy constraint, without exposing either.
+    fn assert_equal(&self, layouter: impl Layouter<F>, a: Self::Num, b: Self::Num) -> Result<(), Error>;
+
+    /// Constrains `a` to equal the fixed `constant`.
+    fn assert_equal_const(&self, layouter: impl Layouter<F>, a: Self::Num, constant: F) -> Result<(), Error>;
+
+    /// Exposes `nums` in consecutive instance rows, starting at `start_row`.
+    fn expose_public_many(
+        &self,
+        layouter: impl Layouter<F>,
+        nums: &[Self::Num],
+        start_row: usize,
+    ) -> Result<(), Error>;
+
+    /// Returns `1 / x` from a witnessed inverse, constraining `x * x_inv == 1`.
+    /// The circuit is unsatisfiable if `x` is zero.
+    fn invert(&self, layouter: impl Layouter<F>, x: Self::Num) -> Result<Self::Num, Error>;
+
+    /// Returns `(x_inv, is_zero)`: `1 / x` and 0 for a nonzero `x`, and 0 and 1
+    /// for a zero one, from `is_zero = 1 - x * x_inv` and `x * is_zero == 0`.
+    fn invert_checked(
+        &self,
+        layouter: impl Layouter<F>,
+        x: Self::Num,
+    ) -> Result<(Self::Num, Self::Num), Error>;
+
+    /// Evaluates the polynomial `sum coeffs[i] * x^i` at `x` with Horner's rule,
+    /// like [`Self::eval_poly`] but for witnessed coefficients and in a single
+    /// region: the accumulator in `advice[0]` alternates with the rows holding
+    /// each coefficient and `x`, and one gate per step computes `acc * x + c`.
+    fn poly_eval(
+        &self,
+        layouter: impl Layouter<F>,
+        coeffs: &[Self::Num],
+        x: Self::Num,
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `sum c_i * x_i` for fixed coefficients in a single region: each
+    /// row holds the running sum and `x_i` in advice, and `c_i` in the fixed
+    /// column shared with constants.
+    fn linear_combination(
+        &self,
+        layouter: impl Layouter<F>,
+        terms: &[(F, Self::Num)],
+    ) -> Result<Self::Num, Error>;
+
+    /// Returns `a_i * b_i` for every pair, packing `WIDTH / 2` independent
+    /// products into each pair of rows of a single region.
+    fn mul_many(
+        &self,
+        layouter: impl Layouter<F>,
+        pairs: &[(Self::Num, Self::Num)],
+    ) -> Result<Vec<Self::Num>, Error>;
+
+    /// Returns 1 if `a == b` and 0 otherwise, from the is-zero gadget's check
+    /// on `a - b`.
+    fn is_equal(&self, layouter: impl Layouter<F>, a: Self::Num, b: Self::Num) -> Result<Self::Num, Error>;
+
+    /// Returns the sum of `xs` like [`Self::accumulate`], but in a single region
+    /// that chains the add gate: each row adds `x_i` in `advice[1]` to the
+    /// running sum in `advice[0]`, whose next row holds the result.
+    fn sum(&self, layouter: impl Layouter<F>, xs: &[Self::Num]) -> Result<Self::Num, Error>;
+
+    fn expose_public(
+        &self,
+        layouter: impl Layouter<F>,
+        num: Self::Num,
+        row: usize,
+    ) -> Result<(), Error>;
+}
+
+pub struct FieldChip<F: FieldExt, const WIDTH: usize = 2> {
+    config: FieldConfig<F, WIDTH>,
+    _marker: PhantomData<F>,
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldConfig<F: FieldExt, const WIDTH: usize = 2> {
+    pub(crate) advice: [Column<Advice>; WIDTH],
+    instance: Column<Instance>,
+    fixed: Column<Fixed>,
+    s_mul: Selector,
+    s_mul_many: Selector,
+    s_add: Selector,
+    s_sub: Selector,
+    s_neg: Selector,
+    s_mul_add: Selector,
+    s_inner_product: Selector,
+    s_horner: Selector,
+    s_linear_combination: Selector,
+    s_select: Selector,
+    s_is_equal: Selector,
+    is_equal: IsZeroConfig<F>,
+    s_bits: Selector,
+}
+
+
+impl<F: FieldExt, const WIDTH: usize> FieldChip<F, WIDTH> {
+    pub fn construct(config: <Self as Chip<F>>::Config) -> Self {
+        Self { 
+            config, 
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configures the chip over `WIDTH >= 2` advice columns. Every instruction
+    /// works on the first two; [`NumericInstructions::mul_many`] spreads across
+    /// all of them, one multiplication per pair of columns.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        advice: [Column<Advice>; WIDTH],
+        instance: Column<Instance>,
+        constant: Column<Fixed>,
+    ) -> <Self as Chip<F>>::Config {
+        assert!(WIDTH >= 2, "the numeric chip needs at least two advice columns");
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+        for column in &advice {
+            meta.enable_equality(*column);
+        }
+        let s_mul = meta.selector();
+        let s_mul_many = meta.selector();
+        let s_add = meta.selector();
+        let s_sub = meta.selector();
+        let s_neg = meta.selector();
+        let s_mul_add = meta.selector();
+        let s_inner_product = meta.selector();
+        let s_horner = meta.selector();
+        let s_linear_combination = meta.selector();
+        let s_select = meta.selector();
+        let s_is_equal = meta.selector();
+        let s_bits = meta.selector();
+
+        meta.create_gate("mul", |meta| {
+            let lhs = meta.query_advice(advice[0], Rotation::cur());
+            let rhs = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[0], Rotation::next());
+            let s_mul = meta.query_selector(s_mul);
+            vec![s_mul * (lhs * rhs - out)]
+        });
+
+        // The mul gate repeated on each pair of columns.
+        meta.create_gate("mul many", |meta| {
+            let s_mul_many = meta.query_selector(s_mul_many);
+            advice
+                .chunks_exact(2)
+                .map(|lane| {
+                    let lhs = meta.query_advice(lane[0], Rotation::cur());
+                    let rhs = meta.query_advice(lane[1], Rotation::cur());
+                    let out = meta.query_advice(lane[0], Rotation::next());
+                    s_mul_many.clone() * (lhs * rhs - out)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("add", |meta| {
+            let lhs = meta.query_advice(advice[0], Rotation::cur());
+            let rhs = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[0], Rotation::next());
+            let s_add = meta.query_selector(s_add);
+            vec![s_add * (lhs + rhs - out)]
+        });
+
+        meta.create_gate("sub", |meta| {
+            let lhs = meta.query_advice(advice[0], Rotation::cur());
+            let rhs = meta.query_advice(advice[1], Rotation::cur());
+            let out = meta.query_advice(advice[0], Rotation::next());
+            let s_sub = meta.query_selector(s_sub);
+            vec![s_sub * (lhs - rhs - out)]
+        });
+
+        meta.create_gate("neg", |meta| {
+            let value = meta.query_advice(advice[0], Rotation::cur());
+            let out = meta.query_advice(advice[0], Rotation::next());
+            let s_neg = meta.query_selector(s_neg);
+            vec![s_neg * (value + out)]
+        });
+
+        // a and b share a row, with the addend and the result on the next.
+        meta.create_gate("mul_add", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let c = meta.query_advice(advice[0], Rotation::next());
+            let out = meta.query_advice(advice[1], Rotation::next());
+            let s_mul_add = meta.query_selector(s_mul_add);
+            vec![s_mul_add * (a * b + c - out)]
+        });
+
+        // The running sum sits in `advice[0]` on the rows around each pair.
+        meta.create_gate("inner product", |meta| {
+            let acc = meta.query_advice(advice[0], Rotation::prev());
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let acc_next = meta.query_advice(advice[0], Rotation::next());
+            let s_inner_product = meta.query_selector(s_inner_product);
+            vec![s_inner_product * (acc + a * b - acc_next)]
+        });
+
+        // Likewise the Horner accumulator, with each coefficient beside `x`.
+        meta.create_gate("horner", |meta| {
+            let acc = meta.query_advice(advice[0], Rotation::prev());
+            let coeff = meta.query_advice(advice[0], Rotation::cur());
+            let x = meta.query_advice(advice[1], Rotation::cur());
+            let acc_next = meta.query_advice(advice[0], Rotation::next());
+            let s_horner = meta.query_selector(s_horner);
+            vec![s_horner * (acc * x + coeff - acc_next)]
+        });
+
+        // The coefficients share the fixed column with the constants.
+        meta.create_gate("linear combination", |meta| {
+            let acc = meta.query_advice(advice[0], Rotation::cur());
+            let x = meta.query_advice(advice[1], Rotation::cur());
+            let coeff = meta.query_fixed(constant, Rotation::cur());
+            let acc_next = meta.query_advice(advice[0], Rotation::next());
+            let s_linear_combination = meta.query_selector(s_linear_combination);
+            vec![s_linear_combination * (acc + coeff * x - acc_next)]
+        });
+
+        meta.create_gate("select", |meta| {
+            let a = meta.query_advice(advice[0], Rotation::cur());
+            let b = meta.query_advice(advice[1], Rotation::cur());
+            let cond = meta.query_advice(advice[0], Rotation::next());
+            let out = meta.query_advice(advice[1], Rotation::next());
+            let s_select = meta.query_selector(s_select);
+            let one = Expression::Constant(F::one());
+            vec![
+                s_select.clone() * cond.clone() * (one.clone() - cond.clone()),
+                s_select * (cond.clone() * a + (one - cond) * b - out),
+            ]
+        });
+
+        // a and b share a row, with the inverse of a - b and the flag on the next.
+        let is_equal = IsZeroChip::configure_with_flag(
+            meta,
+            |meta| meta.query_selector(s_is_equal),
+            |meta| {
+                let a = meta.query_advice(advice[0], Rotation::prev());
+                let b = meta.query_advice(advice[1], Rotation::prev());
+                a - b
+            },
+            advice[0],
+            advice[1],
+        );
+
+        // Bits are consumed most significant first: each row doubles the running
+        // sum in `advice[1]` and adds the boolean bit in `advice[0]`.
+        meta.create_gate("bits", |meta| {
+            let bit = meta.query_advice(advice[0], Rotation::cur());
+            let acc = meta.query_advice(advice[1], Rotation::cur());
+            let acc_next = meta.query_advice(advice[1], Rotation::next());
+            let s_bits = meta.query_selector(s_bits);
+            let one = Expression::Constant(F::one());
+            let two = Expression::Constant(F::from(2));
+            vec![
+                s_bits.clone() * bit.clone() * (one - bit.clone()),
+                s_bits * (acc * two + bit - acc_next),
+            ]
+        });
+
+        FieldConfig {
+            advice,
+            instance,
+            fixed: constant,
+            s_mul,
+            s_mul_many,
+            s_add,
+            s_sub,
+            s_neg,
+            s_mul_add,
+            s_inner_product,
+            s_horner,
+            s_linear_combination,
+            s_select,
+            s_is_equal,
+            is_equal,
+            s_bits,
+        }
+    }
+
+    /// Assigns `lhs` and `rhs` on one row and `out` on the next, under `selector`.
+    fn binary_op(
+        &self,
+        mut layouter: impl Layouter<F>,
+        name: &'static str,
+        selector: Selector,
+        a: Number<F>,
+        b: Number<F>,
+        out: impl Fn(Value<Assigned<F>>, Value<Assigned<F>>) -> Value<Assigned<F>>,
+    ) -> Result<Number<F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
+                b.0.copy_advice(|| "rhs", &mut region, config.advice[1], 0)?;
+
+                let value = out(a.0.value().copied(), b.0.value().copied());
+
+                region
+                    .assign_advice(|| name, config.advice[0], 1, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    /// Witnesses `1 / x`, or zero for a zero `x`, as an unevaluated fraction,
+    /// leaving the inversion to the prover's batch.
+    fn load_inverse(&self, mut layouter: impl Layouter<F>, x: &Number<F>) -> Result<Number<F>, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load inverse",
+            |mut region| {
+                let inverse = x.0.value().map(|x| x.invert());
+                region
+                    .assign_advice(|| "inverse", config.advice[0], 0, || inverse)
+                    .map(Number)
+            },
+        )
+    }
+
+    pub(crate) fn constrain_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Number<F>,
+        b: &Number<F>,
+    ) -> Result<(), Error> {
+        let config = self.config();
+
+        // The copy keeps the region non-empty, which MockProver needs to
+        // locate failures.
+        layouter.assign_region(
+            || "a == b",
+            |mut region| {
+                let a = a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                region.constrain_equal(a.cell(), b.0.cell())
+            },
+        )
+    }
+
+    /// Returns 1 if `value` is zero and 0 otherwise, through the is-zero gadget
+    /// behind [`NumericInstructions::is_equal`].
+    pub(crate) fn is_zero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        let zero = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        self.is_equal(layouter.namespace(|| "value == 0?"), value, zero)
+    }
+
+    /// Proves `a * x + n * y == 1` for witnessed Bezout coefficients, with `x` in
+    /// `[0, n)` and `y = -q` for a `BITS`-bit `q`. `a` is range checked to `BITS`
+    /// bits; `n > 1` must fit in `BITS` bits as well.
+    fn assert_bezout<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Number<F>,
+        n: Number<F>,
+    ) -> Result<(), Error> {
+        // The range checks keep a * x and n * q below 2^(2 * BITS), so the field
+        // equation a * x == n * q + 1 also holds over the integers.
+        let coefficients = a.evaluate().as_ref().zip(n.evaluate().as_ref()).map(|(a, n)| {
+            let (a, n) = (a.get_lower_128(), n.get_lower_128());
+            invert_mod(a, n)
+                .and_then(|x| Some((F::from_u128(x), F::from_u128(a.checked_mul(x)?.checked_sub(1)? / n))))
+                .unwrap_or((F::zero(), F::zero()))
+        });
+
+        let x = self.load_private(layouter.namespace(|| "load x"), coefficients.map(|(x, _)| x))?;
+        let q = self.load_private(layouter.namespace(|| "load q"), coefficients.map(|(_, q)| q))?;
+
+        self.decompose_running_sum(layouter.namespace(|| "range check a"), a.clone(), BITS)?;
+        self.decompose_running_sum(layouter.namespace(|| "range check q"), q.clone(), BITS)?;
+        self.assert_less_than::<BITS>(layouter.namespace(|| "x < n"), x.clone(), n.clone())?;
+
+        let ax = self.mul(layouter.namespace(|| "a * x"), a, x)?;
+        let nq = self.mul(layouter.namespace(|| "n * q"), n, q)?;
+        let nq_plus_one = self.add_constant(layouter.namespace(|| "n * q + 1"), nq, F::one())?;
+        self.constrain_equal(layouter.namespace(|| "a * x + n * y == 1"), &ax, &nq_plus_one)
+    }
+
+    /// Decomposes `value` into `num_digits` base-`base` digits, least significant
+    /// first. Each digit is shown to be `< base` by range checking both `digit`
+    /// and `base - 1 - digit`.
+    pub(crate) fn decompose_base(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Number<F>,
+        num_digits: usize,
+        base: u64,
+    ) -> Result<Vec<Number<F>>, Error> {
+        assert!(base >= 2, "base must be at least 2");
+        let digit_bits = (u64::BITS - (base - 1).leading_zeros()) as usize;
+
+        let value_int = value.evaluate().as_ref().map(|v| v.get_lower_128());
+        let mut digits = Vec::with_capacity(num_digits);
+        for i in 0..num_digits {
+            let digit = value_int.map(|v| F::from_u128(v / (base as u128).pow(i as u32) % base as u128));
+            let digit = self.load_private(layouter.namespace(|| "load digit"), digit)?;
+
+            self.decompose_running_sum(layouter.namespace(|| "digit >= 0"), digit.clone(), digit_bits)?;
+            let complement = self.mul_by_constant(layouter.namespace(|| "-digit"), digit.clone(), -F::one())?;
+            let complement =
+                self.add_constant(layouter.namespace(|| "base - 1 - digit"), complement, F::from(base - 1))?;
+            self.decompose_running_sum(layouter.namespace(|| "digit < base"), complement, digit_bits)?;
+
+            digits.push(digit);
+        }
+
+        let (top, rest) = digits.split_last().ok_or(Error::Synthesis)?;
+        let mut acc = top.clone();
+        for digit in rest.iter().rev() {
+            acc = self.mul_by_constant(layouter.namespace(|| "acc * base"), acc, F::from(base))?;
+            acc = self.add(layouter.namespace(|| "acc + digit"), acc, digit.clone())?;
+        }
+        self.constrain_equal(layouter.namespace(|| "value == digits"), &value, &acc)?;
+
+        Ok(digits)
+    }
+
+    /// Decomposes `value` into `num_bits` bits. Returns the bits least significant
+    /// first, together with the running sums `value >> j` for `j` in `0..=num_bits`.
+    fn decompose_running_sum(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Number<F>,
+        num_bits: usize,
+    ) -> Result<RunningSum<F>, Error> {
+        self.running_sum(layouter, value, num_bits, None)
+    }
+
+    /// Splits `value` into a witnessed `high` and its low `low_bits` bits, with
+    /// `value == high * 2^low_bits + low`, and returns `high` range checked to
+    /// `high_bits` bits.
+    fn split_low_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Number<F>,
+        low_bits: usize,
+        high_bits: usize,
+        high: Value<F>,
+    ) -> Result<Number<F>, Error> {
+        let split = layouter.namespace(|| "split low bits");
+        let (_, mut shifted) = self.running_sum(split, value, low_bits, Some(high))?;
+        let high = shifted.swap_remove(low_bits);
+        self.decompose_running_sum(layouter.namespace(|| "range check high"), high.clone(), high_bits)?;
+        Ok(high)
+    }
+
+    /// Decomposes the low `num_bits` bits of `value`, starting the running sum
+    /// from zero or, if given, from a witnessed high part.
+    fn running_sum(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Number<F>,
+        num_bits: usize,
+        high: Option<Value<F>>,
+    ) -> Result<RunningSum<F>, Error> {
+        assert!(num_bits <= 128, "decomposition is limited to 128 bits");
+        let config = self.config();
+
+        layouter.assign_region(
+            || "decompose",
+            |mut region| {
+                let mut bits = Vec::with_capacity(num_bits);
+                let mut shifted = Vec::with_capacity(num_bits + 1);
+
+                let mut acc = match high {
+                    Some(high) => {
+                        region.assign_advice(|| "high", config.advice[1], 0, || high.map(Assigned::from))?
+                    }
+                    None => {
+                        region.assign_advice_from_constant(|| "acc", config.advice[1], 0, F::zero().into())?
+                    }
+                };
+                shifted.push(Number(acc.clone()));
+                // The bits are those of `value - high * 2^num_bits`.
+                let scaled_high = acc.value().map(|high| (0..num_bits).fold(*high, |acc, _| acc.double()));
+                let low = (value.0.value().copied() - scaled_high).evaluate();
+
+                for row in 0..num_bits {
+                    config.s_bits.enable(&mut region, row)?;
+
+                    let index = num_bits - 1 - row;
+                    let bit_value = low
+                        .map(|v| Assigned::from(F::from(((v.get_lower_128() >> index) & 1) as u64)));
+                    let bit = region.assign_advice(|| "bit", config.advice[0], row, || bit_value)?;
+
+                    let acc_value = acc.value().map(|acc| acc.double()) + bit.value();
+                    acc = if row + 1 == num_bits {
+                        value.0.copy_advice(|| "value", &mut region, config.advice[1], row + 1)?
+                    } else {
+                        region.assign_advice(|| "acc", config.advice[1], row + 1, || acc_value)?
+                    };
+
+                    bits.push(Number(bit));
+                    shifted.push(Number(acc.clone()));
+                }
+
+                if num_bits == 0 {
+                    region.constrain_equal(acc.cell(), value.0.cell())?;
+                }
+
+                bits.reverse();
+                shifted.reverse();
+                Ok((bits, shifted))
+            },
+        )
+    }
+}
+
+impl<F: FieldExt, const WIDTH: usize> Chip<F> for FieldChip<F, WIDTH> {
+    type Config = FieldConfig<F, WIDTH>;
+    type Loaded = ();
+    
+    fn config(&self) -> &Self::Config {
+        &self.config
+    }
+
+    fn loaded(&self) -> &Self::Loaded {
+        &()
+    }
+}
+
+/// A cell assigned by the chip. Its value is kept as an `Assigned` fraction, so
+/// arithmetic on inverses stays deferred and the prover inverts every such
+/// cell in a single batch.
+#[derive(Clone)]
+pub struct Number<F: FieldExt>(pub(crate) AssignedCell<Assigned<F>, F>);
+
+impl<F: FieldExt> Number<F> {
+    /// The witnessed value, unknown during key generation.
+    pub fn value(&self) -> Value<&Assigned<F>> {
+        self.0.value()
+    }
+
+    /// The witnessed value as a field element, inverting its denominator on the
+    /// spot. Only needed where a witness depends on the value's integer form.
+    pub fn evaluate(&self) -> Value<F> {
+        self.0.value_field().evaluate()
+    }
+
+    /// The assigned cell, for copy constraints in other chips' regions.
+    pub fn cell(&self) -> Cell {
+        self.0.cell()
+    }
+}
+
+/// Bits of a decomposed value, least significant first, and its running sums.
+type RunningSum<F> = (Vec<Number<F>>, Vec<Number<F>>);
+
+impl<F: FieldExt, const WIDTH: usize> NumericInstructions<F> for FieldChip<F, WIDTH> {
+    type Num = Number<F>;
+
+    fn load_private(&self, mut layouter: impl Layouter<F>, value: Value<F>) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load private",
+            |mut region| {
+                region
+                    .assign_advice(|| "private input", config.advice[0], 0, || value.map(Assigned::from))
+                    .map(Number)
+            },
+        )
+    }
+
+    fn load_privates(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[Value<F>],
+    ) -> Result<Vec<Self::Num>, Error> {
+        let config = self.config();
+        let width = config.advice.len();
+
+        layouter.assign_region(
+            || "load privates",
+            |mut region| {
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| {
+                        region
+                            .assign_advice(
+                                || "private input",
+                                config.advice[i % width],
+                                i / width,
+                                || value.map(Assigned::from),
+                            )
+                            .map(Number)
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    fn load_constant(&self, mut layouter: impl Layouter<F>, constant: F) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load constant", 
+            |mut region| {
+                region
+                    .assign_advice_from_constant(
+                        || "constant value",
+                        config.advice[0],
+                        0,
+                        Assigned::from(constant),
+                    )
+                    .map(Number)
+            },
+        )
+    }
+
+    fn load_instance(&self, mut layouter: impl Layouter<F>, row: usize) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "load instance",
+            |mut region| {
+                // Instance cells only come back as plain field values, so the
+                // input is copied once more into a fraction-valued cell.
+                let public = region.assign_advice_from_instance(
+                    || "public input",
+                    config.instance,
+                    row,
+                    config.advice[0],
+                    0,
+                )?;
+                let number = region.assign_advice(
+                    || "public input",
+                    config.advice[1],
+                    0,
+                    || public.value().map(Assigned::from),
+                )?;
+                region.constrain_equal(public.cell(), number.cell())?;
+                Ok(Number(number))
+            },
+        )
+    }
+
+    fn mul(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "mul", 
+            |mut region| {
+                config.s_mul.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
+                b.0.copy_advice(|| "rhs", &mut region, config.advice[1], 0)?;
+
+                let value = a.0.value().copied() * b.0.value();
+
+                region
+                    .assign_advice(|| "lhs * rhs", config.advice[0], 1, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        self.binary_op(layouter, "lhs + rhs", self.config.s_add, a, b, |a, b| a + b)
+    }
+
+    fn sub(
+        &self,
+        layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        self.binary_op(layouter, "lhs - rhs", self.config.s_sub, a, b, |a, b| a - b)
+    }
+
+    fn neg(&self, mut layouter: impl Layouter<F>, a: Self::Num) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "neg",
+            |mut region| {
+                config.s_neg.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "value", &mut region, config.advice[0], 0)?;
+
+                region
+                    .assign_advice(|| "-value", config.advice[0], 1, || -a.0.value().copied())
+                    .map(Number)
+            },
+        )
+    }
+
+    fn mul_add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+        c: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "mul_add",
+            |mut region| {
+                config.s_mul_add.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.0.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                c.0.copy_advice(|| "c", &mut region, config.advice[0], 1)?;
+
+                let value = a.0.value().copied() * b.0.value() + c.0.value();
+
+                region
+                    .assign_advice(|| "a * b + c", config.advice[1], 1, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: Self::Num,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                config.s_select.enable(&mut region, 0)?;
+
+                a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.0.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+                cond.0.copy_advice(|| "cond", &mut region, config.advice[0], 1)?;
+
+                let value = cond.value().zip(a.value()).zip(b.value()).map(|((cond, a), b)| {
+                    *cond * a + (Assigned::from(F::one()) - cond) * b
+                });
+
+                region
+                    .assign_advice(|| "cond ? a : b", config.advice[1], 1, || value)
+                    .map(Number)
+            },
+        )
+    }
+
+    fn mul_by_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        constant: F,
+    ) -> Result<Self::Num, Error> {
+        let constant = self.load_constant(layouter.namespace(|| "load constant"), constant)?;
+        self.mul(layouter.namespace(|| "a * constant"), a, constant)
+    }
+
+    fn add_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        constant: F,
+    ) -> Result<Self::Num, Error> {
+        let constant = self.load_constant(layouter.namespace(|| "load constant"), constant)?;
+        self.add(layouter.namespace(|| "a + constant"), a, constant)
+    }
+
+    fn decompose<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+    ) -> Result<Vec<Self::Num>, Error> {
+        let (bits, _) = self.decompose_running_sum(layouter, value, BITS)?;
+        Ok(bits)
+    }
+
+    fn compose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: &[Self::Num],
+    ) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "compose",
+            |mut region| {
+                let mut acc = region
+                    .assign_advice_from_constant(|| "acc", config.advice[1], 0, Assigned::from(F::zero()))?;
+
+                for (row, bit) in bits.iter().rev().enumerate() {
+                    config.s_bits.enable(&mut region, row)?;
+
+                    let bit = bit.0.copy_advice(|| "bit", &mut region, config.advice[0], row)?;
+                    let acc_value = acc.value().map(|acc| acc.double()) + bit.value();
+                    acc = region.assign_advice(|| "acc", config.advice[1], row + 1, || acc_value)?;
+                }
+
+                Ok(Number(acc))
+            },
+        )
+    }
+
+    fn shift_right<const BITS: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+        shift: usize,
+    ) -> Result<Self::Num, Error> {
+        assert!(shift <= BITS);
+        let (_, mut shifted) = self.decompose_running_sum(layouter, value, BITS)?;
+        Ok(shifted.swap_remove(shift))
+    }
+
+    fn less_than<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        // b - a - 1 + 2^BITS lies in [0, 2^(BITS+1)) and reaches the top bit
+        // exactly when a < b.
+        let offset = F::from_u128(1 << BITS) - F::one();
+        let diff = self.sub(layouter.namespace(|| "b - a"), b, a)?;
+        let diff = self.add_constant(layouter.namespace(|| "b - a - 1 + 2^BITS"), diff, offset)?;
+        let mut bits = self.decompose_running_sum(layouter.namespace(|| "decompose"), diff, BITS + 1)?.0;
+        Ok(bits.pop().unwrap())
+    }
+
+    fn assert_less_than<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<(), Error> {
+        // b - a - 1 fits in BITS bits exactly when a < b.
+        let diff = self.sub(layouter.namespace(|| "b - a"), b, a)?;
+        let diff = self.add_constant(layouter.namespace(|| "b - a - 1"), diff, -F::one())?;
+        self.decompose_running_sum(layouter.namespace(|| "range check"), diff, BITS)?;
+        Ok(())
+    }
+
+    fn assert_less_equal<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<(), Error> {
+        // b - a fits in BITS bits exactly when a <= b.
+        let diff = self.sub(layouter.namespace(|| "b - a"), b, a)?;
+        self.decompose_running_sum(layouter.namespace(|| "range check"), diff, BITS)?;
+        Ok(())
+    }
+
+    fn ema_update<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        prev: Self::Num,
+        sample: Self::Num,
+        alpha_num: u64,
+        alpha_den: u64,
+    ) -> Result<Self::Num, Error> {
+        assert!(alpha_den.is_power_of_two(), "alpha_den must be a power of two");
+        assert!(alpha_num <= alpha_den, "alpha must not exceed one");
+
+        // alpha_den * prev + alpha_num * (sample - prev) stays non-negative, so the
+        // division by alpha_den can be done with a plain right shift.
+        let diff = self.sub(layouter.namespace(|| "sample - prev"), sample, prev.clone())?;
+        let step = self.mul_by_constant(
+            layouter.namespace(|| "alpha_num * diff"),
+            diff,
+            F::from(alpha_num),
+        )?;
+        let base = self.mul_by_constant(
+            layouter.namespace(|| "alpha_den * prev"),
+            prev,
+            F::from(alpha_den),
+        )?;
+        let sum = self.add(layouter.namespace(|| "base + step"), base, step)?;
+
+        self.shift_right::<BITS>(
+            layouter.namespace(|| "sum / alpha_den"),
+            sum,
+            alpha_den.trailing_zeros() as usize,
+        )
+    }
+
+    fn accumulate(
+        &self,
+        mut layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+    ) -> Result<Self::Num, Error> {
+        let mut acc = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        for x in xs {
+            acc = self.add(layouter.namespace(|| "acc + x"), acc, x.clone())?;
+        }
+        Ok(acc)
+    }
+
+    fn assert_equal_sums(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &[Self::Num],
+        b: &[Self::Num],
+    ) -> Result<(), Error> {
+        let sum_a = self.accumulate(layouter.namespace(|| "sum a"), a)?;
+        let sum_b = self.accumulate(layouter.namespace(|| "sum b"), b)?;
+
+        self.constrain_equal(layouter.namespace(|| "sum a == sum b"), &sum_a, &sum_b)
+    }
+
+    fn round<const BITS: usize, const SCALE: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        assert!(0 < SCALE && SCALE <= BITS);
+
+        // The fractional remainder is the low SCALE bits, so it is >= 0.5
+        // exactly when its top bit is set.
+        let (bits, mut shifted) =
+            self.decompose_running_sum(layouter.namespace(|| "decompose"), value, BITS)?;
+        let half = bits[SCALE - 1].clone();
+        let floor = shifted.swap_remove(SCALE);
+
+        self.add(layouter.namespace(|| "floor + half"), floor, half)
+    }
+
+    fn to_twos_complement<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Self::Num,
+    ) -> Result<Vec<Self::Num>, Error> {
+        assert!(BITS > 0);
+
+        // Offsetting by 2^(BITS-1) maps the signed range onto [0, 2^BITS) and
+        // flips only the top bit of the pattern, which becomes the sign bit.
+        let offset = F::from_u128(1 << (BITS - 1));
+        let biased = self.add_constant(layouter.namespace(|| "value + 2^(BITS-1)"), value, offset)?;
+        let mut bits = self.decompose::<BITS>(layouter.namespace(|| "decompose"), biased)?;
+
+        let one = self.load_constant(layouter.namespace(|| "one"), F::one())?;
+        let top = bits.pop().unwrap();
+        let sign = self.sub(layouter.namespace(|| "sign bit"), one, top)?;
+        bits.push(sign);
+
+        Ok(bits)
+    }
+
+    fn assert_divisible<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Self::Num,
+        divisor: Self::Num,
+    ) -> Result<(), Error> {
+        // For an exact division the field quotient is the integer quotient;
+        // otherwise it is a large field element that fails the range check.
+        let quotient = value.evaluate().as_ref().zip(divisor.evaluate().as_ref()).map(|(value, divisor)| {
+            divisor.invert().map(|inv| *value * inv).unwrap_or(F::zero())
+        });
+        let quotient = self.load_private(layouter.namespace(|| "load quotient"), quotient)?;
+        self.decompose_running_sum(layouter.namespace(|| "range check quotient"), quotient.clone(), BITS)?;
+
+        let product = self.mul(layouter.namespace(|| "divisor * quotient"), divisor, quotient)?;
+        self.constrain_equal(layouter.namespace(|| "value == divisor * quotient"), &value, &product)
+    }
+
+    fn eval_poly(
+        &self,
+        mut layouter: impl Layouter<F>,
+        coeffs: &[F],
+        x: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let (leading, rest) = coeffs.split_last().expect("polynomial has no coefficients");
+
+        let mut acc = self.load_constant(layouter.namespace(|| "leading coefficient"), *leading)?;
+        for coeff in rest.iter().rev() {
+            acc = self.mul(layouter.namespace(|| "acc * x"), acc, x.clone())?;
+            acc = self.add_constant(layouter.namespace(|| "acc + coeff"), acc, *coeff)?;
+        }
+
+        Ok(acc)
+    }
+
+    fn assert_transpose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &[Vec<Self::Num>],
+        b: &[Vec<Self::Num>],
+    ) -> Result<(), Error> {
+        let columns = a.first().map_or(0, Vec::len);
+        let a_rectangular = a.iter().all(|row| row.len() == columns);
+        let b_rectangular = b.iter().all(|row| row.len() == a.len());
+        if b.len() != columns || !a_rectangular || !b_rectangular {
+            return Err(Error::Synthesis);
+        }
+
+        for (i, row) in a.iter().enumerate() {
+            for (j, entry) in row.iter().enumerate() {
+                self.constrain_equal(layouter.namespace(|| "b[j][i] == a[i][j]"), entry, &b[j][i])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn dot(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &[Self::Num],
+        b: &[Self::Num],
+    ) -> Result<Self::Num, Error> {
+        if a.len() != b.len() {
+            return Err(Error::Synthesis);
+        }
+
+        let products = a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| self.mul(layouter.namespace(|| "a_i * b_i"), a.clone(), b.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.accumulate(layouter.namespace(|| "sum of products"), &products)
+    }
+
+    fn inner_product(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &[Self::Num],
+        b: &[Self::Num],
+    ) -> Result<Self::Num, Error> {
+        if a.len() != b.len() {
+            return Err(Error::Synthesis);
+        }
+        let config = self.config();
+
+        layouter.assign_region(
+            || "inner product",
+            |mut region| {
+                let mut acc = region
+                    .assign_advice_from_constant(|| "acc", config.advice[0], 0, Assigned::from(F::zero()))?;
+
+                for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                    let row = 2 * i + 1;
+                    config.s_inner_product.enable(&mut region, row)?;
+
+                    a.0.copy_advice(|| "a_i", &mut region, config.advice[0], row)?;
+                    b.0.copy_advice(|| "b_i", &mut region, config.advice[1], row)?;
+
+                    let value = acc.value().copied() + a.0.value().copied() * b.0.value();
+                    acc = region.assign_advice(|| "acc + a_i * b_i", config.advice[0], row + 1, || value)?;
+                }
+
+                Ok(Number(acc))
+            },
+        )
+    }
+
+    fn floor_div_pow2<const BITS: usize, const K: usize>(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let high = value
+            .evaluate()
+            .map(|v| F::from_u128(v.get_lower_128().checked_shr(K as u32).unwrap_or(0)));
+        self.split_low_bits(layouter, value, K, BITS - K, high)
+    }
+
+    fn assert_arithmetic_progression(
+        &self,
+        mut layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+    ) -> Result<Self::Num, Error> {
+        let diffs = xs
+            .windows(2)
+            .map(|pair| self.sub(layouter.namespace(|| "x_{i+1} - x_i"), pair[1].clone(), pair[0].clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (first, rest) = diffs.split_first().ok_or(Error::Synthesis)?;
+
+        for diff in rest {
+            self.constrain_equal(layouter.namespace(|| "equal differences"), first, diff)?;
+        }
+
+        Ok(first.clone())
+    }
+
+    fn assert_geometric_progression(
+        &self,
+        mut layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+    ) -> Result<Self::Num, Error> {
+        if xs.len() < 2 {
+            return Err(Error::Synthesis);
+        }
+
+        let ratio = xs[1].evaluate().as_ref().zip(xs[0].evaluate().as_ref()).map(|(next, first)| {
+            first.invert().map(|inv| *next * inv).unwrap_or(F::zero())
+        });
+        let ratio = self.load_private(layouter.namespace(|| "load ratio"), ratio)?;
+
+        for pair in xs.windows(2) {
+            let next = self.mul(layouter.namespace(|| "x_i * r"), pair[0].clone(), ratio.clone())?;
+            self.constrain_equal(layouter.namespace(|| "x_{i+1} == x_i * r"), &pair[1], &next)?;
+        }
+
+        Ok(ratio)
+    }
+
+    fn has_real_roots(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+        c: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let b_squared = self.mul(layouter.namespace(|| "b^2"), b.clone(), b)?;
+        let ac = self.mul(layouter.namespace(|| "a * c"), a, c)?;
+        let four_ac = self.mul_by_constant(layouter.namespace(|| "4ac"), ac, F::from(4))?;
+        let disc = self.sub(layouter.namespace(|| "b^2 - 4ac"), b_squared, four_ac)?;
+
+        // g is a non-residue, so exactly one of d and g * d is a square when d is
+        // non-zero. The witnessed root s shows whichever one it is.
+        let g = F::multiplicative_generator();
+        let is_square = disc.evaluate().as_ref().map(|d| bool::from(d.sqrt().is_some()));
+        let root = disc.evaluate().as_ref().zip(is_square).map(|(d, is_square)| {
+            let target = if is_square { *d } else { g * d };
+            target.sqrt().unwrap()
+        });
+
+        let flag = is_square.map(|is_square| F::from(is_square as u64));
+        let flag = self.load_private(layouter.namespace(|| "load flag"), flag)?;
+        self.decompose::<1>(layouter.namespace(|| "flag is boolean"), flag.clone())?;
+
+        // s^2 == d * (g + flag * (1 - g))
+        let factor =
+            self.mul_by_constant(layouter.namespace(|| "flag * (1 - g)"), flag.clone(), F::one() - g)?;
+        let factor = self.add_constant(layouter.namespace(|| "g + flag * (1 - g)"), factor, g)?;
+        let target = self.mul(layouter.namespace(|| "d * factor"), disc.clone(), factor)?;
+        let root = self.load_private(layouter.namespace(|| "load root"), root)?;
+        let root_squared = self.mul(layouter.namespace(|| "s^2"), root.clone(), root)?;
+        self.constrain_equal(layouter.namespace(|| "s^2 == target"), &root_squared, &target)?;
+
+        // Zero is a square, but so is g * 0: rule out flag = 0 by showing d has
+        // an inverse whenever the flag is unset.
+        let inv = disc.evaluate().as_ref().map(|d| d.invert().unwrap_or(F::zero()));
+        let inv = self.load_private(layouter.namespace(|| "load d^-1"), inv)?;
+        let product = self.mul(layouter.namespace(|| "d * d^-1"), disc, inv)?;
+        let product = self.add_constant(layouter.namespace(|| "d * d^-1 - 1"), product, -F::one())?;
+        let not_flag = self.mul_by_constant(layouter.namespace(|| "-flag"), flag.clone(), -F::one())?;
+        let not_flag = self.add_constant(layouter.namespace(|| "1 - flag"), not_flag, F::one())?;
+        let gated = self.mul(layouter.namespace(|| "(1 - flag) * (d * d^-1 - 1)"), not_flag, product)?;
+        let zero = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        self.constrain_equal(layouter.namespace(|| "d != 0 unless flag"), &gated, &zero)?;
+
+        Ok(flag)
+    }
+
+    fn isqrt<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        n: Self::Num,
+    ) -> Result<(Self::Num, Self::Num), Error> {
+        let root = n.evaluate().as_ref().map(|n| {
+            let n = n.get_lower_128();
+            // Correct the floating-point estimate, which is off by at most a few
+            // units for large n.
+            let mut r = (n as f64).sqrt() as u128;
+            while r.checked_mul(r).is_none_or(|sq| sq > n) {
+                r -= 1;
+            }
+            while (r + 1).checked_mul(r + 1).is_some_and(|sq| sq <= n) {
+                r += 1;
+            }
+            F::from_u128(r)
+        });
+        let root = self.load_private(layouter.namespace(|| "load root"), root)?;
+        self.decompose_running_sum(layouter.namespace(|| "range check root"), root.clone(), BITS)?;
+
+        // r^2 <= n < (r + 1)^2, i.e. 0 <= n - r^2 <= 2r.
+        let square = self.mul(layouter.namespace(|| "r^2"), root.clone(), root.clone())?;
+        let rem = self.sub(layouter.namespace(|| "n - r^2"), n, square)?;
+        let twice_root = self.add(layouter.namespace(|| "2r"), root.clone(), root.clone())?;
+        self.assert_less_equal::<BITS>(layouter.namespace(|| "n - r^2 <= 2r"), rem.clone(), twice_root)?;
+        self.decompose_running_sum(layouter.namespace(|| "n - r^2 >= 0"), rem.clone(), BITS)?;
+
+        Ok((root, rem))
+    }
+
+    fn assert_perfect_square<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        n: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let (_, rem) = self.isqrt::<BITS>(layouter.namespace(|| "isqrt"), n)?;
+        self.is_zero(layouter.namespace(|| "remainder is zero"), rem)
+    }
+
+    fn toy_hash(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let b = self.mul_by_constant(layouter.namespace(|| "3b"), b, F::from(3))?;
+        let x = self.add(layouter.namespace(|| "a + 3b"), a, b)?;
+        let x = self.add_constant(layouter.namespace(|| "a + 3b + 7"), x, F::from(7))?;
+
+        let x2 = self.mul(layouter.namespace(|| "x^2"), x.clone(), x.clone())?;
+        let x4 = self.mul(layouter.namespace(|| "x^4"), x2.clone(), x2)?;
+        self.mul(layouter.namespace(|| "x^5"), x4, x)
+    }
+
+    fn square(&self, layouter: impl Layouter<F>, x: Self::Num) -> Result<Self::Num, Error> {
+        self.mul(layouter, x.clone(), x)
+    }
+
+    fn assert_within_std_devs<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sample: Self::Num,
+        mean: Self::Num,
+        variance: Self::Num,
+        n: u64,
+    ) -> Result<(), Error> {
+        // The square of the field difference is the integer square whichever of
+        // sample and mean is larger.
+        let diff = self.sub(layouter.namespace(|| "sample - mean"), sample, mean)?;
+        let deviation = self.square(layouter.namespace(|| "(sample - mean)^2"), diff)?;
+        let bound =
+            self.mul_by_constant(layouter.namespace(|| "n^2 * variance"), variance, F::from(n).square())?;
+        self.assert_less_equal::<BITS>(layouter.namespace(|| "deviation <= bound"), deviation, bound)
+    }
+
+    fn assert_bits_equal_public<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Self::Num,
+        instance_start: usize,
+    ) -> Result<(), Error> {
+        let bits = self.decompose::<BITS>(layouter.namespace(|| "decompose"), value)?;
+        for (i, bit) in bits.into_iter().enumerate() {
+            self.expose_public(layouter.namespace(|| "bit == public"), bit, instance_start + i)?;
+        }
+        Ok(())
+    }
+
+    fn sub_saturating<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let below = self.less_than::<BITS>(layouter.namespace(|| "a < b"), a.clone(), b.clone())?;
+        let one = self.load_constant(layouter.namespace(|| "one"), F::one())?;
+        let keep = self.sub(layouter.namespace(|| "1 - (a < b)"), one, below)?;
+
+        let diff = self.sub(layouter.namespace(|| "a - b"), a, b)?;
+        self.mul(layouter.namespace(|| "(a - b) * keep"), diff, keep)
+    }
+
+    fn assert_distinct_power_sum<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Self::Num,
+        selected: &[Self::Num],
+    ) -> Result<(), Error> {
+        if selected.len() > BITS {
+            return Err(Error::Synthesis);
+        }
+
+        // compose boolean-checks each selector as it accumulates.
+        let sum = self.compose(layouter.namespace(|| "sum selected powers"), selected)?;
+        self.constrain_equal(layouter.namespace(|| "value == sum"), &value, &sum)
+    }
+
+    fn lagrange_eval(
+        &self,
+        mut layouter: impl Layouter<F>,
+        points: &[(F, F)],
+        x: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let diffs = points
+            .iter()
+            .map(|(x_m, _)| self.add_constant(layouter.namespace(|| "x - x_m"), x.clone(), -*x_m))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut terms = Vec::with_capacity(points.len());
+        for (j, (x_j, y_j)) in points.iter().enumerate() {
+            // The nodes are constants, so each basis denominator is inverted
+            // outside the circuit.
+            let denominator = points
+                .iter()
+                .enumerate()
+                .filter(|(m, _)| *m != j)
+                .fold(F::one(), |acc, (_, (x_m, _))| acc * (*x_j - x_m));
+            let weight = Option::<F>::from(denominator.invert()).ok_or(Error::Synthesis)? * y_j;
+
+            let mut term = self.load_constant(layouter.namespace(|| "y_j / denominator"), weight)?;
+            for (m, diff) in diffs.iter().enumerate() {
+                if m != j {
+                    term = self.mul(layouter.namespace(|| "term * (x - x_m)"), term, diff.clone())?;
+                }
+            }
+            terms.push(term);
+        }
+
+        self.accumulate(layouter.namespace(|| "sum of terms"), &terms)
+    }
+
+    fn is_palindrome<const DIGITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Self::Num,
+        base: u64,
+    ) -> Result<Self::Num, Error> {
+        let digits = self.decompose_base(layouter.namespace(|| "digits"), value, DIGITS, base)?;
+
+        let mut flag = self.load_constant(layouter.namespace(|| "one"), F::one())?;
+        for i in 0..DIGITS / 2 {
+            let diff = self.sub(
+                layouter.namespace(|| "d_i - d_{DIGITS-1-i}"),
+                digits[i].clone(),
+                digits[DIGITS - 1 - i].clone(),
+            )?;
+            let matches = self.is_zero(layouter.namespace(|| "d_i == d_{DIGITS-1-i}"), diff)?;
+            flag = self.mul(layouter.namespace(|| "flag * matches"), flag, matches)?;
+        }
+
+        Ok(flag)
+    }
+
+    fn piecewise_linear<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: Self::Num,
+        breakpoints: &[F],
+        slopes: &[F],
+        intercepts: &[F],
+    ) -> Result<Self::Num, Error> {
+        if slopes.len() != breakpoints.len() + 1 || intercepts.len() != slopes.len() {
+            return Err(Error::Synthesis);
+        }
+
+        let lines = slopes
+            .iter()
+            .zip(intercepts)
+            .map(|(slope, intercept)| {
+                let y = self.mul_by_constant(layouter.namespace(|| "slope * x"), x.clone(), *slope)?;
+                self.add_constant(layouter.namespace(|| "slope * x + intercept"), y, *intercept)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // Start from the first line and, at every breakpoint x has reached,
+        // switch to the next line by adding the difference between the two.
+        let one = self.load_constant(layouter.namespace(|| "one"), F::one())?;
+        let mut y = lines[0].clone();
+        for (breakpoint, pair) in breakpoints.iter().zip(lines.windows(2)) {
+            let breakpoint = self.load_constant(layouter.namespace(|| "breakpoint"), *breakpoint)?;
+            let below =
+                self.less_than::<BITS>(layouter.namespace(|| "x < breakpoint"), x.clone(), breakpoint)?;
+            let reached = self.sub(layouter.namespace(|| "x >= breakpoint"), one.clone(), below)?;
+
+            let step = self.sub(layouter.namespace(|| "next - current"), pair[1].clone(), pair[0].clone())?;
+            let step = self.mul(layouter.namespace(|| "reached * step"), reached, step)?;
+            y = self.add(layouter.namespace(|| "y + step"), y, step)?;
+        }
+
+        Ok(y)
+    }
+
+    fn assert_coprime<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        n: F,
+    ) -> Result<(), Error> {
+        let n = self.load_constant(layouter.namespace(|| "n"), n)?;
+        self.assert_bezout::<BITS>(layouter.namespace(|| "bezout"), a, n)
+    }
+
+    fn max<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        // a + (a < b) * (b - a)
+        let a_lt_b = self.less_than::<BITS>(layouter.namespace(|| "a < b"), a.clone(), b.clone())?;
+        let diff = self.sub(layouter.namespace(|| "b - a"), b, a.clone())?;
+        let step = self.mul(layouter.namespace(|| "(a < b) * (b - a)"), a_lt_b, diff)?;
+        self.add(layouter.namespace(|| "max"), a, step)
+    }
+
+    fn running_max<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+    ) -> Result<Vec<Self::Num>, Error> {
+        let (first, rest) = xs.split_first().ok_or(Error::Synthesis)?;
+
+        let mut maxima = vec![first.clone()];
+        for x in rest {
+            let prev = maxima.last().unwrap().clone();
+            maxima.push(self.max::<BITS>(layouter.namespace(|| "max(prev, x)"), prev, x.clone())?);
+        }
+
+        Ok(maxima)
+    }
+
+    fn factorial(&self, mut layouter: impl Layouter<F>, n: usize) -> Result<Self::Num, Error> {
+        let one = self.load_constant(layouter.namespace(|| "0!"), F::one())?;
+
+        (2..=n as u64).try_fold(one, |acc, i| {
+            self.mul_by_constant(layouter.namespace(|| "acc * i"), acc, F::from(i))
+        })
+    }
+
+    fn assert_binomial(
+        &self,
+        mut layouter: impl Layouter<F>,
+        n: u64,
+        k: u64,
+        claimed: Self::Num,
+    ) -> Result<(), Error> {
+        if k > n {
+            return Err(Error::Synthesis);
+        }
+
+        let k_factorial = self.factorial(layouter.namespace(|| "k!"), k as usize)?;
+        let rest_factorial = self.factorial(layouter.namespace(|| "(n - k)!"), (n - k) as usize)?;
+        let n_factorial = self.factorial(layouter.namespace(|| "n!"), n as usize)?;
+
+        let product = self.mul(layouter.namespace(|| "claimed * k!"), claimed, k_factorial)?;
+        let product = self.mul(layouter.namespace(|| "claimed * k! * (n - k)!"), product, rest_factorial)?;
+        self.constrain_equal(layouter.namespace(|| "product == n!"), &product, &n_factorial)
+    }
+
+    fn validate_bounds<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        values: &[(Self::Num, F, F)],
+    ) -> Result<(), Error> {
+        for (value, min, max) in values {
+            let min = self.load_constant(layouter.namespace(|| "min"), *min)?;
+            let max = self.load_constant(layouter.namespace(|| "max"), *max)?;
+
+            self.assert_less_equal::<BITS>(layouter.namespace(|| "min <= value"), min, value.clone())?;
+            self.assert_less_than::<BITS>(layouter.namespace(|| "value < max"), value.clone(), max)?;
+        }
+
+        Ok(())
+    }
+
+    fn assert_mod_sqrt<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        y: Self::Num,
+        x: Self::Num,
+        n: F,
+    ) -> Result<(), Error> {
+        let mod_chip = ModReduceChip::construct(self.config.clone());
+
+        let x_squared = self.square(layouter.namespace(|| "x * x"), x)?;
+        let x_squared = mod_chip.reduce::<BITS>(layouter.namespace(|| "x * x mod n"), x_squared, n)?;
+        let y = mod_chip.reduce::<BITS>(layouter.namespace(|| "y mod n"), y, n)?;
+        self.constrain_equal(layouter.namespace(|| "x * x == y (mod n)"), &x_squared, &y)
+    }
+
+    fn assert_lowest_terms<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        num: Self::Num,
+        den: Self::Num,
+    ) -> Result<(), Error> {
+        // Unlike a constant modulus, a witnessed denominator needs its own range check.
+        self.decompose_running_sum(layouter.namespace(|| "range check den"), den.clone(), BITS)?;
+        self.assert_bezout::<BITS>(layouter.namespace(|| "bezout"), num, den)
+    }
+
+    fn assert_kth_smallest<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+        k: usize,
+        claimed: Self::Num,
+    ) -> Result<(), Error> {
+        let (first, rest) = xs.split_first().ok_or(Error::Synthesis)?;
+
+        // claimed is in the set exactly when prod(x_i - claimed) == 0.
+        let diff = self.sub(layouter.namespace(|| "x_0 - claimed"), first.clone(), claimed.clone())?;
+        let product = rest.iter().try_fold(diff, |product, x| {
+            let diff = self.sub(layouter.namespace(|| "x_i - claimed"), x.clone(), claimed.clone())?;
+            self.mul(layouter.namespace(|| "product * diff"), product, diff)
+        })?;
+        let zero = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        self.constrain_equal(layouter.namespace(|| "claimed in xs"), &product, &zero)?;
+
+        let flags = xs
+            .iter()
+            .map(|x| {
+                self.less_than::<BITS>(layouter.namespace(|| "x_i < claimed"), x.clone(), claimed.clone())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let count = self.accumulate(layouter.namespace(|| "count"), &flags)?;
+        let k = self.load_constant(layouter.namespace(|| "k"), F::from(k as u64))?;
+        self.constrain_equal(layouter.namespace(|| "count == k"), &count, &k)
+    }
+
+    fn assert_nonnegative_balance<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        deltas: &[Self::Num],
+    ) -> Result<Self::Num, Error> {
+        let zero = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+
+        deltas.iter().try_fold(zero.clone(), |balance, delta| {
+            let balance = self.add(layouter.namespace(|| "balance + delta"), balance, delta.clone())?;
+
+            let sign = self
+                .to_twos_complement::<BITS>(layouter.namespace(|| "two's complement"), balance.clone())?
+                .pop()
+                .unwrap();
+            self.constrain_equal(layouter.namespace(|| "balance >= 0"), &sign, &zero)?;
+
+            Ok(balance)
+        })
+    }
+
+    fn pack_bytes<const N: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bytes: &[Self::Num],
+    ) -> Result<Self::Num, Error> {
+        // Packed values stay below 2^(8 * N), which must not wrap the field.
+        if bytes.len() > N || 8 * N >= F::NUM_BITS as usize {
+            return Err(Error::Synthesis);
+        }
+
+        let mut packed = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        for byte in bytes {
+            self.decompose::<8>(layouter.namespace(|| "byte < 256"), byte.clone())?;
+            packed = self.mul_by_constant(layouter.namespace(|| "packed * 256"), packed, F::from(256))?;
+            packed = self.add(layouter.namespace(|| "packed + byte"), packed, byte.clone())?;
+        }
+
+        Ok(packed)
+    }
+
+    fn sort_pair<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<(Self::Num, Self::Num), Error> {
+        // max as in `max`, and min = a + b - max keeps the pair's multiset.
+        let max = self.max::<BITS>(layouter.namespace(|| "max"), a.clone(), b.clone())?;
+        let sum = self.add(layouter.namespace(|| "a + b"), a, b)?;
+        let min = self.sub(layouter.namespace(|| "min"), sum, max.clone())?;
+        Ok((min, max))
+    }
+
+    fn windowed_sum(
+        &self,
+        mut layouter: impl Layouter<F>,
+        xs: &[Self::Num],
+        window: usize,
+    ) -> Result<Vec<Self::Num>, Error> {
+        if window == 0 || window > xs.len() {
+            return Err(Error::Synthesis);
+        }
+
+        let mut prefix = vec![self.load_constant(layouter.namespace(|| "prefix_0"), F::zero())?];
+        for x in xs {
+            let last = prefix.last().unwrap().clone();
+            prefix.push(self.add(layouter.namespace(|| "prefix + x"), last, x.clone())?);
+        }
+
+        prefix
+            .iter()
+            .zip(&prefix[window..])
+            .map(|(start, end)| self.sub(layouter.namespace(|| "end - start"), end.clone(), start.clone()))
+            .collect()
+    }
+
+    fn assert_canonical(&self, mut layouter: impl Layouter<F>, digits: &[Self::Num]) -> Result<(), Error> {
+        let (top, rest) = digits.split_first().ok_or(Error::Synthesis)?;
+
+        // A zero leading digit forces every other digit to zero.
+        let top_is_zero = self.is_zero(layouter.namespace(|| "top == 0"), top.clone())?;
+        let zero = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        for digit in rest {
+            let product =
+                self.mul(layouter.namespace(|| "(top == 0) * digit"), top_is_zero.clone(), digit.clone())?;
+            self.constrain_equal(layouter.namespace(|| "digit == 0 if top == 0"), &product, &zero)?;
+        }
+
+        Ok(())
+    }
+
+    fn assert_no_overlap<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a_start: Self::Num,
+        a_end: Self::Num,
+        b_start: Self::Num,
+        b_end: Self::Num,
+    ) -> Result<(), Error> {
+        // The OR of a_end <= b_start and b_end <= a_start fails exactly when
+        // both negations b_start < a_end and a_start < b_end hold.
+        let a_past_b = self.less_than::<BITS>(layouter.namespace(|| "b_start < a_end"), b_start, a_end)?;
+        let b_past_a = self.less_than::<BITS>(layouter.namespace(|| "a_start < b_end"), a_start, b_end)?;
+        let overlap = self.mul(layouter.namespace(|| "overlap"), a_past_b, b_past_a)?;
+
+        let zero = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        self.constrain_equal(layouter.namespace(|| "no overlap"), &overlap, &zero)
+    }
+
+    fn pow_const(
+        &self,
+        mut layouter: impl Layouter<F>,
+        base: Self::Num,
+        exp: u64,
+    ) -> Result<Self::Num, Error> {
+        if exp == 0 {
+            return self.load_constant(layouter.namespace(|| "base^0"), F::one());
+        }
+
+        // Consume the exponent most significant bit first, after the leading one.
+        let mut acc = base.clone();
+        for bit in (0..63 - exp.leading_zeros()).rev() {
+            acc = self.square(layouter.namespace(|| "acc^2"), acc)?;
+            if exp >> bit & 1 == 1 {
+                acc = self.mul(layouter.namespace(|| "acc * base"), acc, base.clone())?;
+            }
+        }
+
+        Ok(acc)
+    }
+
+    fn assert_not_divisible<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Self::Num,
+        divisor: Self::Num,
+    ) -> Result<(), Error> {
+        let value_int = value.evaluate().as_ref().map(|value| value.get_lower_128());
+        let divisor_int = divisor.evaluate().as_ref().map(|divisor| divisor.get_lower_128());
+        let division = value_int.zip(divisor_int).map(|(value, divisor)| {
+            value.checked_div(divisor).zip(value.checked_rem(divisor)).unwrap_or((0, 0))
+        });
+
+        let quotient = division.map(|(quotient, _)| F::from_u128(quotient));
+        let quotient = self.load_private(layouter.namespace(|| "load quotient"), quotient)?;
+        self.decompose_running_sum(layouter.namespace(|| "range check quotient"), quotient.clone(), BITS)?;
+
+        // 0 < remainder < divisor: a nonzero remainder is what rules out divisibility.
+        let remainder = division.map(|(_, remainder)| F::from_u128(remainder));
+        let remainder = self.load_private(layouter.namespace(|| "load remainder"), remainder)?;
+        let zero = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        self.assert_less_than::<BITS>(layouter.namespace(|| "0 < remainder"), zero, remainder.clone())?;
+        self.assert_less_than::<BITS>(
+            layouter.namespace(|| "remainder < divisor"),
+            remainder.clone(),
+            divisor.clone(),
+        )?;
+
+        let product = self.mul(layouter.namespace(|| "divisor * quotient"), divisor, quotient)?;
+        let sum = self.add(layouter.namespace(|| "divisor * quotient + remainder"), product, remainder)?;
+        self.constrain_equal(layouter.namespace(|| "value == divisor * quotient + remainder"), &value, &sum)
+    }
+
+    fn assert_prime_power_exponent<const BITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        n: Self::Num,
+        p: F,
+        e: u64,
+    ) -> Result<(), Error> {
+        let p = self.load_constant(layouter.namespace(|| "p"), p)?;
+        let power = self.pow_const(layouter.namespace(|| "p^e"), p.clone(), e)?;
+        let next_power = self.mul(layouter.namespace(|| "p^(e+1)"), power.clone(), p)?;
+
+        self.assert_divisible::<BITS>(layouter.namespace(|| "p^e | n"), n.clone(), power)?;
+        self.assert_not_divisible::<BITS>(layouter.namespace(|| "p^(e+1) does not divide n"), n, next_power)
+    }
+
+    fn assert_one_of(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Self::Num,
+        set: &[F],
+    ) -> Result<(), Error> {
+        let (first, rest) = set.split_first().ok_or(Error::Synthesis)?;
+
+        let mut product = self.add_constant(layouter.namespace(|| "value - s"), value.clone(), -*first)?;
+        for s in rest {
+            let diff = self.add_constant(layouter.namespace(|| "value - s"), value.clone(), -*s)?;
+            product = self.mul(layouter.namespace(|| "product * (value - s)"), product, diff)?;
+        }
+
+        let zero = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        self.constrain_equal(layouter.namespace(|| "value in set"), &product, &zero)
+    }
+
+    fn to_balanced_ternary<const DIGITS: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Self::Num,
+    ) -> Result<Vec<Self::Num>, Error> {
+        // Negative values are witnessed through their negation, with every digit flipped.
+        let digit_values = value.evaluate().as_ref().map(|v| {
+            let (mut magnitude, negative) = match v.get_lower_128() {
+                small if F::from_u128(small) == *v => (small, false),
+                _ => ((-*v).get_lower_128(), true),
+            };
+            (0..DIGITS)
+                .map(|_| {
+                    let digit = match magnitude % 3 {
+                        2 => -F::one(),
+                        digit => F::from_u128(digit),
+                    };
+                    magnitude = (magnitude + 1) / 3;
+                    if negative { -digit } else { digit }
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let set = [-F::one(), F::zero(), F::one()];
+        let mut digits = Vec::with_capacity(DIGITS);
+        for i in 0..DIGITS {
+            let digit = digit_values.as_ref().map(|digits| digits[i]);
+            let digit = self.load_private(layouter.namespace(|| "load digit"), digit)?;
+            self.assert_one_of(layouter.namespace(|| "digit in {-1, 0, 1}"), digit.clone(), &set)?;
+            digits.push(digit);
+        }
+
+        let (top, rest) = digits.split_last().ok_or(Error::Synthesis)?;
+        let mut acc = top.clone();
+        for digit in rest.iter().rev() {
+            acc = self.mul_by_constant(layouter.namespace(|| "acc * 3"), acc, F::from(3))?;
+            acc = self.add(layouter.namespace(|| "acc + digit"), acc, digit.clone())?;
+        }
+        self.constrain_equal(layouter.namespace(|| "value == digits"), &value, &acc)?;
+
+        Ok(digits)
+    }
+
+    fn reciprocal<const BITS: usize, const SCALE: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let scale = 1u128 << SCALE;
+        let r = x.evaluate().as_ref().map(|x| {
+            let x = x.get_lower_128();
+            F::from_u128((scale + x / 2).checked_div(x).unwrap_or(0))
+        });
+        let r = self.load_private(layouter.namespace(|| "load r"), r)?;
+        self.decompose_running_sum(layouter.namespace(|| "range check r"), r.clone(), BITS)?;
+
+        // |x * r - 2^SCALE| < x, as 0 < x * r - 2^SCALE + x < 2 * x.
+        let product = self.mul(layouter.namespace(|| "x * r"), x.clone(), r.clone())?;
+        let shifted = self.add(layouter.namespace(|| "x * r + x"), product, x.clone())?;
+        let shifted =
+            self.add_constant(layouter.namespace(|| "x * r + x - 2^SCALE"), shifted, -F::from_u128(scale))?;
+        let zero = self.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+        self.assert_less_than::<BITS>(layouter.namespace(|| "0 < shifted"), zero, shifted.clone())?;
+        let two_x = self.mul_by_constant(layouter.namespace(|| "2 * x"), x, F::from(2))?;
+        self.assert_less_than::<BITS>(layouter.namespace(|| "shifted < 2 * x"), shifted, two_x)?;
+
+        Ok(r)
+    }
+
+    fn div(&self, mut layouter: impl Layouter<F>, a: Self::Num, b: Self::Num) -> Result<Self::Num, Error> {
+        let b_inv = self.invert(layouter.namespace(|| "1 / b"), b)?;
+        self.mul(layouter.namespace(|| "a * b_inv"), a, b_inv)
+    }
+
+    fn assert_equal(&self, layouter: impl Layouter<F>, a: Self::Num, b: Self::Num) -> Result<(), Error> {
+        self.constrain_equal(layouter, &a, &b)
+    }
+
+    fn assert_equal_const(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        constant: F,
+    ) -> Result<(), Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "a == constant",
+            |mut region| {
+                let a = a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                region.constrain_constant(a.cell(), constant)
+            },
+        )
+    }
+
+    fn expose_public_many(
+        &self,
+        mut layouter: impl Layouter<F>,
+        nums: &[Self::Num],
+        start_row: usize,
+    ) -> Result<(), Error> {
+        for (row, num) in (start_row..).zip(nums) {
+            self.expose_public(layouter.namespace(|| "expose"), num.clone(), row)?;
+        }
+
+        Ok(())
+    }
+
+    fn invert(&self, mut layouter: impl Layouter<F>, x: Self::Num) -> Result<Self::Num, Error> {
+        // A zero has no inverse; witnessing zero makes x * x_inv == 1 fail.
+        let x_inv = self.load_inverse(layouter.namespace(|| "load x_inv"), &x)?;
+
+        let product = self.mul(layouter.namespace(|| "x * x_inv"), x, x_inv.clone())?;
+        self.assert_equal_const(layouter.namespace(|| "x * x_inv == 1"), product, F::one())?;
+
+        Ok(x_inv)
+    }
+
+    fn invert_checked(
+        &self,
+        mut layouter: impl Layouter<F>,
+        x: Self::Num,
+    ) -> Result<(Self::Num, Self::Num), Error> {
+        let x_inv = self.load_inverse(layouter.namespace(|| "load x_inv"), &x)?;
+
+        let product = self.mul(layouter.namespace(|| "x * x_inv"), x.clone(), x_inv.clone())?;
+        let one = self.load_constant(layouter.namespace(|| "one"), F::one())?;
+        let is_zero = self.sub(layouter.namespace(|| "1 - x * x_inv"), one, product)?;
+
+        let gated = self.mul(layouter.namespace(|| "x * is_zero"), x, is_zero.clone())?;
+        self.assert_equal_const(layouter.namespace(|| "x * is_zero == 0"), gated, F::zero())?;
+
+        Ok((x_inv, is_zero))
+    }
+
+    fn poly_eval(
+        &self,
+        mut layouter: impl Layouter<F>,
+        coeffs: &[Self::Num],
+        x: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let (leading, rest) = coeffs.split_last().ok_or(Error::Synthesis)?;
+        let config = self.config();
+
+        layouter.assign_region(
+            || "horner",
+            |mut region| {
+                let mut acc = leading.0.copy_advice(|| "leading", &mut region, config.advice[0], 0)?;
+
+                for (i, coeff) in rest.iter().rev().enumerate() {
+                    let row = 2 * i + 1;
+                    config.s_horner.enable(&mut region, row)?;
+
+                    coeff.0.copy_advice(|| "coeff", &mut region, config.advice[0], row)?;
+                    x.0.copy_advice(|| "x", &mut region, config.advice[1], row)?;
+
+                    let value = acc.value().copied() * x.0.value() + coeff.0.value();
+                    acc = region.assign_advice(|| "acc * x + coeff", config.advice[0], row + 1, || value)?;
+                }
+
+                Ok(Number(acc))
+            },
+        )
+    }
+
+    fn linear_combination(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[(F, Self::Num)],
+    ) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "linear combination",
+            |mut region| {
+                let mut acc = region
+                    .assign_advice_from_constant(|| "acc", config.advice[0], 0, Assigned::from(F::zero()))?;
+
+                for (row, (coeff, x)) in terms.iter().enumerate() {
+                    config.s_linear_combination.enable(&mut region, row)?;
+
+                    region.assign_fixed(|| "c_i", config.fixed, row, || Value::known(*coeff))?;
+                    x.0.copy_advice(|| "x_i", &mut region, config.advice[1], row)?;
+
+                    let value = acc.value().copied() + x.0.value().map(|x| x * *coeff);
+                    acc = region.assign_advice(|| "acc + c_i * x_i", config.advice[0], row + 1, || value)?;
+                }
+
+                Ok(Number(acc))
+            },
+        )
+    }
+
+    fn mul_many(
+        &self,
+        mut layouter: impl Layouter<F>,
+        pairs: &[(Self::Num, Self::Num)],
+    ) -> Result<Vec<Self::Num>, Error> {
+        let config = self.config();
+        let lanes = WIDTH / 2;
+
+        layouter.assign_region(
+            || "mul many",
+            |mut region| {
+                let mut products = Vec::with_capacity(pairs.len());
+
+                for (i, chunk) in pairs.chunks(lanes).enumerate() {
+                    let row = 2 * i;
+                    config.s_mul_many.enable(&mut region, row)?;
+
+                    for lane in 0..lanes {
+                        let (lhs, rhs) = (config.advice[2 * lane], config.advice[2 * lane + 1]);
+                        match chunk.get(lane) {
+                            Some((a, b)) => {
+                                a.0.copy_advice(|| "lhs", &mut region, lhs, row)?;
+                                b.0.copy_advice(|| "rhs", &mut region, rhs, row)?;
+                                let value = a.0.value().copied() * b.0.value();
+                                let out = region.assign_advice(|| "lhs * rhs", lhs, row + 1, || value)?;
+                                products.push(Number(out));
+                            }
+                            // The gate covers every lane, so pad the last row pair with 0 * 0 = 0.
+                            None => {
+                                let zero = || Value::known(F::zero());
+                                for (column, offset) in [(lhs, row), (rhs, row), (lhs, row + 1)] {
+                                    region.assign_advice(|| "padding", column, offset, zero)?;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                Ok(products)
+            },
+        )
+    }
+
+    fn is_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: Self::Num,
+        b: Self::Num,
+    ) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "a == b?",
+            |mut region| {
+                a.0.copy_advice(|| "a", &mut region, config.advice[0], 0)?;
+                b.0.copy_advice(|| "b", &mut region, config.advice[1], 0)?;
+
+                config.s_is_equal.enable(&mut region, 1)?;
+                let diff = a.0.value().copied() - b.0.value();
+                let is_zero_chip = IsZeroChip::construct(config.is_equal.clone());
+                let (flag, _) = is_zero_chip.assign_with_flag(&mut region, 1, diff)?;
+                Ok(Number(flag))
+            },
+        )
+    }
+
+    fn sum(&self, mut layouter: impl Layouter<F>, xs: &[Self::Num]) -> Result<Self::Num, Error> {
+        let config = self.config();
+
+        layouter.assign_region(
+            || "sum",
+            |mut region| {
+                let mut acc = region
+                    .assign_advice_from_constant(|| "acc", config.advice[0], 0, Assigned::from(F::zero()))?;
+
+                for (row, x) in xs.iter().enumerate() {
+                    config.s_add.enable(&mut region, row)?;
+
+                    x.0.copy_advice(|| "x_i", &mut region, config.advice[1], row)?;
+
+                    let value = acc.value().copied() + x.0.value();
+                    acc = region.assign_advice(|| "acc + x_i", config.advice[0], row + 1, || value)?;
+                }
+
+                Ok(Number(acc))
+            },
+        )
+    }
+
+    fn expose_public(
+            &self,
+            mut layouter: impl Layouter<F>,
+            num: Self::Num,
+            row: usize,
+    ) -> Result<(), Error> {
+        let config = self.config();
+
+        layouter.constrain_instance(num.0.cell(), config.instance, row)
+    }
+}
+
+
+#[derive(Default)]
+#[allow(dead_code)]
+pub(crate) struct MyCircuit<F: FieldExt> {
+    pub(crate) constant: F,
+    pub(crate) a: Value<F>,
+    pub(crate) b: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config);
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+
+        let constant = field_chip.load_constant(layouter.namespace(|| "load constant"), self.constant)?;
+        let ab = field_chip.mul(layouter.namespace(|| "a * b"), a, b)?;
+        let ab_sq = field_chip.mul(layouter.namespace(|| "ab * ab"), ab.clone(), ab.clone())?;
+        let c = field_chip.mul(layouter.namespace(|| "constant * ab_sq"), constant, ab_sq.clone())?;
+
+        field_chip.expose_public_many(layouter.namespace(|| "expose ab, ab^2 and c"), &[ab, ab_sq, c], 0)
+    }
+}
+
+/// Computes `constant * (a * b + a + b)`, fusing the product and the first
+/// addition into a single `mul_add`.
+#[derive(Default)]
+#[allow(dead_code)]
+pub(crate) struct AffineCircuit<F: FieldExt> {
+    pub(crate) constant: F,
+    pub(crate) a: Value<F>,
+    pub(crate) b: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for AffineCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            constant: self.constant,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MyCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config);
+        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+
+        let ab_a = field_chip.mul_add(layouter.namespace(|| "a * b + a"), a.clone(), b.clone(), a)?;
+        let sum = field_chip.add(layouter.namespace(|| "ab + a + b"), ab_a, b)?;
+        let c = field_chip.mul_by_constant(layouter.namespace(|| "constant * sum"), sum, self.constant)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose c"), c, 0)
+    }
+}
+
+/// Exposes the inner product of two private vectors.
+#[derive(Default)]
+#[allow(dead_code)]
+pub(crate) struct InnerProductCircuit<F: FieldExt> {
+    pub(crate) a: Vec<Value<F>>,
+    pub(crate) b: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for InnerProductCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            a: vec![Value::unknown(); self.a.len()],
+            b: vec![Value::unknown(); self.b.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MyCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config);
+        let a = field_chip.load_privates(layouter.namespace(|| "load a"), &self.a)?;
+        let b = field_chip.load_privates(layouter.namespace(|| "load b"), &self.b)?;
+
+        let product = field_chip.inner_product(layouter.namespace(|| "a . b"), &a, &b)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose a . b"), product, 0)
+    }
+}
+
+/// Proves knowledge of a root of the public polynomial whose `degree + 1`
+/// coefficients, constant term first, fill the instance column.
+#[derive(Default)]
+#[allow(dead_code)]
+pub(crate) struct PolyRootCircuit<F: FieldExt> {
+    pub(crate) degree: usize,
+    pub(crate) root: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for PolyRootCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            degree: self.degree,
+            root: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MyCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config);
+        let coeffs = (0..=self.degree)
+            .map(|row| field_chip.load_instance(layouter.namespace(|| "load coefficient"), row))
+            .collect::<Result<Vec<_>, _>>()?;
+        let root = field_chip.load_private(layouter.namespace(|| "load root"), self.root)?;
+
+        let value = field_chip.poly_eval(layouter.namespace(|| "p(root)"), &coeffs, root)?;
+        field_chip.assert_equal_const(layouter.namespace(|| "p(root) == 0"), value, F::zero())
+    }
+}
+
+/// Proves that the private values sum to the public total in instance row 0.
+#[derive(Default)]
+#[allow(dead_code)]
+pub(crate) struct SumCircuit<F: FieldExt> {
+    pub(crate) xs: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for SumCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            xs: vec![Value::unknown(); self.xs.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MyCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let field_chip = FieldChip::<F>::construct(config);
+        let xs = field_chip.load_privates(layouter.namespace(|| "load xs"), &self.xs)?;
+
+        let total = field_chip.sum(layouter.namespace(|| "sum xs"), &xs)?;
+        field_chip.expose_public(layouter.namespace(|| "expose total"), total, 0)
+    }
+}
+
+/// Exposes the products of private pairs, computed `WIDTH / 2` at a time.
+#[derive(Default)]
+#[allow(dead_code)]
+pub(crate) struct ProductsCircuit<F: FieldExt, const WIDTH: usize> {
+    pub(crate) pairs: Vec<(Value<F>, Value<F>)>,
+}
+
+impl<F: FieldExt, const WIDTH: usize> Circuit<F> for ProductsCircuit<F, WIDTH> {
+    type Config = FieldConfig<F, WIDTH>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            pairs: vec![(Value::unknown(), Value::unknown()); self.pairs.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [(); WIDTH].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let field_chip = FieldChip::<F, WIDTH>::construct(config);
+        let values = self.pairs.iter().flat_map(|&(a, b)| [a, b]).collect::<Vec<_>>();
+        let values = field_chip.load_privates(layouter.namespace(|| "load pairs"), &values)?;
+        let pairs = values
+            .chunks_exact(2)
+            .map(|pair| (pair[0].clone(), pair[1].clone()))
+            .collect::<Vec<_>>();
+
+        let products = field_chip.mul_many(layouter.namespace(|| "a_i * b_i"), &pairs)?;
+        field_chip.expose_public_many(layouter.namespace(|| "expose products"), &products, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        pasta::{group::ff::{Field, PrimeField}, Fp}, 
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        plonk::{Circuit, ConstraintSystem, Error},
+    };
+    use super::{
+        AffineCircuit, FieldChip, FieldConfig, InnerProductCircuit, MyCircuit, NumericInstructions, Number,
+        PolyRootCircuit, ProductsCircuit, SumCircuit,
+    };
+
+    /// A piece of logic synthesized on top of a `FieldChip`, so each gadget test
+    /// only has to describe its own witnesses and wiring.
+    trait Gadget: Default {
+        fn synthesize(&self, chip: &FieldChip<Fp>, layouter: impl Layouter<Fp>) -> Result<(), Error>;
+    }
+
+    #[derive(Default)]
+    struct GadgetCircuit<G: Gadget>(G);
+
+    impl<G: Gadget> Circuit<Fp> for GadgetCircuit<G> {
+        type Config = FieldConfig<Fp>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            MyCircuit::<Fp>::configure(meta)
+        }
+
+        fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            self.0.synthesize(&FieldChip::construct(config), layouter)
+        }
+    }
+
+    fn run<G: Gadget>(k: u32, gadget: G, public_input: Vec<Fp>) -> MockProver<Fp> {
+        MockProver::run(k, &GadgetCircuit(gadget), vec![public_input]).unwrap()
+    }
+
+    fn known(values: &[u64]) -> Vec<Value<Fp>> {
+        values.iter().map(|v| Value::known(Fp::from(*v))).collect()
+    }
+
+    fn signed(value: i64) -> Fp {
+        if value < 0 {
+            -Fp::from(value.unsigned_abs())
+        } else {
+            Fp::from(value as u64)
+        }
+    }
+
+    fn load_all(
+        chip: &FieldChip<Fp>,
+        layouter: &mut impl Layouter<Fp>,
+        values: &[Value<Fp>],
+    ) -> Result<Vec<Number<Fp>>, Error> {
+        chip.load_privates(layouter.namespace(|| "load"), values)
+    }
+    
+    #[test]
+    fn numeric_instructions() {
+
+        let k = 4;
+        let constant = Fp::from(7);
+        let a = Fp::from(2);
+        let b = Fp::from(3);
+        let ab = a * b;
+        let c = constant * ab.square();
+
+        let circuit = MyCircuit {
+            constant,
+            a: Value::known(a),
+            b: Value::known(b),
+        };
+
+        let public_input = vec![ab, ab.square(), c];
+
+        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        for row in 0..public_input.len() {
+            let mut public_input = public_input.clone();
+            public_input[row] += Fp::one();
+            let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[derive(Default)]
+    struct SubNeg {
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Gadget for SubNeg {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let a = chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+
+            let diff = chip.sub(layouter.namespace(|| "a - b"), a.clone(), b)?;
+            let neg = chip.neg(layouter.namespace(|| "-a"), a)?;
+
+            chip.expose_public(layouter.namespace(|| "expose a - b"), diff, 0)?;
+            chip.expose_public(layouter.namespace(|| "expose -a"), neg, 1)
+        }
+    }
+
+    #[test]
+    fn sub_and_neg() {
+        let k = 4;
+        let gadget = |a: i64, b: i64| SubNeg {
+            a: Value::known(signed(a)),
+            b: Value::known(signed(b)),
+        };
+
+        for (a, b) in [(7, 3), (3, 7), (0, 0), (-5, 2)] {
+            run(k, gadget(a, b), vec![signed(a - b), signed(-a)]).assert_satisfied();
+        }
+
+        // Swapped operands, and a negation that leaves the value alone.
+        assert!(run(k, gadget(7, 3), vec![signed(-4), signed(-7)]).verify().is_err());
+        assert!(run(k, gadget(7, 3), vec![signed(4), signed(7)]).verify().is_err());
+    }
+
+    /// The affine demo without `mul_add`, chaining a `mul` and an `add` region.
+    #[derive(Default)]
+    struct ChainedAffine {
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Gadget for ChainedAffine {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let a = chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+
+            let ab = chip.mul(layouter.namespace(|| "a * b"), a.clone(), b.clone())?;
+            let ab_a = chip.add(layouter.namespace(|| "ab + a"), ab, a)?;
+            let sum = chip.add(layouter.namespace(|| "ab + a + b"), ab_a, b)?;
+            let c = chip.mul_by_constant(layouter.namespace(|| "7 * sum"), sum, Fp::from(7))?;
+
+            chip.expose_public(layouter.namespace(|| "expose c"), c, 0)
+        }
+    }
+
+    #[derive(Default)]
+    struct LoadPrivates {
+        values: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for LoadPrivates {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let mut values = chip.load_privates(layouter.namespace(|| "load values"), &self.values)?;
+            chip.expose_public(layouter.namespace(|| "expose last"), values.pop().unwrap(), 1)?;
+            chip.expose_public(layouter.namespace(|| "expose first"), values.swap_remove(0), 0)
+        }
+    }
+
+    #[test]
+    fn load_privates() {
+        let k = 4;
+        let values = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8];
+        let ends = vec![Fp::from(3), Fp::from(8)];
+
+        // Twelve values take six rows; a region each would take twelve.
+        let gadget = || LoadPrivates { values: known(&values) };
+        run(k, gadget(), ends.clone()).assert_satisfied();
+        assert!(run(k, gadget(), vec![Fp::from(3), Fp::from(5)]).verify().is_err());
+
+        let one_per_region = GadgetCircuit(LoadAll { values: known(&values) });
+        assert!(MockProver::run(k, &one_per_region, vec![ends]).is_err());
+    }
+
+    /// Loads every value in a region of its own.
+    #[derive(Default)]
+    struct LoadAll {
+        values: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for LoadAll {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let mut values = self
+                .values
+                .iter()
+                .map(|value| chip.load_private(layouter.namespace(|| "load value"), *value))
+                .collect::<Result<Vec<_>, _>>()?;
+            chip.expose_public(layouter.namespace(|| "expose last"), values.pop().unwrap(), 1)?;
+            chip.expose_public(layouter.namespace(|| "expose first"), values.swap_remove(0), 0)
+        }
+    }
+
+    #[test]
+    fn inner_product() {
+        let k = 5;
+        let circuit = |a: &[u64], b: &[u64]| InnerProductCircuit {
+            a: known(a),
+            b: known(b),
+        };
+        let (a, b) = ([1, 2, 3, 4], [5, 6, 7, 8]);
+
+        // 1 * 5 + 2 * 6 + 3 * 7 + 4 * 8 = 70.
+        let prover = MockProver::run(k, &circuit(&a, &b), vec![vec![Fp::from(70)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(&[], &[]), vec![vec![Fp::zero()]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(&a, &b), vec![vec![Fp::from(69)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        assert!(MockProver::run(k, &circuit(&a[..2], &b[..1]), vec![vec![Fp::from(5)]]).is_err());
+    }
+
+    #[derive(Default)]
+    struct Select {
+        cond: Value<Fp>,
+    }
+
+    impl Gadget for Select {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let cond = chip.load_private(layouter.namespace(|| "load cond"), self.cond)?;
+            let a = chip.load_constant(layouter.namespace(|| "a"), Fp::from(10))?;
+            let b = chip.load_constant(layouter.namespace(|| "b"), Fp::from(20))?;
+            let out = chip.select(layouter.namespace(|| "cond ? a : b"), cond, a, b)?;
+            chip.expose_public(layouter.namespace(|| "expose out"), out, 0)
+        }
+    }
+
+    #[test]
+    fn select() {
+        let k = 4;
+        let gadget = |cond: u64| Select {
+            cond: Value::known(Fp::from(cond)),
+        };
+
+        run(k, gadget(1), vec![Fp::from(10)]).assert_satisfied();
+        run(k, gadget(0), vec![Fp::from(20)]).assert_satisfied();
+        assert!(run(k, gadget(1), vec![Fp::from(20)]).verify().is_err());
+
+        // cond = 2 gives 2 * 10 - 20 = 0, but it is not boolean.
+        assert!(run(k, gadget(2), vec![Fp::zero()]).verify().is_err());
+    }
+
+    #[test]
+    fn affine_expression() {
+        let k = 4;
+        let constant = Fp::from(7);
+        let circuit = |a: u64, b: u64| AffineCircuit {
+            constant,
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        };
+
+        // 7 * (2 * 3 + 2 + 3) = 77.
+        let prover = MockProver::run(k, &circuit(2, 3), vec![vec![Fp::from(77)]]).unwrap();
+        prover.assert_satisfied();
+
+        // Dropping either affine term changes the result.
+        for wrong in [constant * Fp::from(6), constant * Fp::from(8), constant * Fp::from(9)] {
+            let prover = MockProver::run(k, &circuit(2, 3), vec![vec![wrong]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        // Chaining mul and add takes an extra region, which no longer fits.
+        let chained = GadgetCircuit(ChainedAffine {
+            a: Value::known(Fp::from(2)),
+            b: Value::known(Fp::from(3)),
+        });
+        assert!(MockProver::run(k, &chained, vec![vec![Fp::from(77)]]).is_err());
+        run(k + 1, chained.0, vec![Fp::from(77)]).assert_satisfied();
+    }
+
+    #[derive(Default)]
+    struct Ema {
+        prev: Value<Fp>,
+        samples: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for Ema {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let mut prev = chip.load_private(layouter.namespace(|| "load prev"), self.prev)?;
+            for (row, sample) in self.samples.iter().enumerate() {
+                let sample = chip.load_private(layouter.namespace(|| "load sample"), *sample)?;
+                prev = chip.ema_update::<16>(layouter.namespace(|| "ema"), prev, sample, 1, 4)?;
+                chip.expose_public(layouter.namespace(|| "expose ema"), prev.clone(), row)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn ema_update() {
+        let k = 7;
+        let samples = [180u64, 60, 61];
+
+        let mut expected = vec![];
+        let mut prev = 100u64;
+        for sample in samples {
+            prev = (4 * prev + sample - prev) / 4;
+            expected.push(Fp::from(prev));
+        }
+        assert_eq!(expected, vec![Fp::from(120), Fp::from(105), Fp::from(94)]);
+
+        let ema = || Ema {
+            prev: Value::known(Fp::from(100)),
+            samples: known(&samples),
+        };
+
+        run(k, ema(), expected.clone()).assert_satisfied();
+
+        expected[1] += Fp::one();
+        assert!(run(k, ema(), expected).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct EqualSums {
+        inputs: Vec<Value<Fp>>,
+        outputs: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for EqualSums {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let inputs = load_all(chip, &mut layouter, &self.inputs)?;
+            let outputs = load_all(chip, &mut layouter, &self.outputs)?;
+
+            chip.assert_equal_sums(layouter.namespace(|| "balance"), &inputs, &outputs)
+        }
+    }
+
+    #[test]
+    fn assert_equal_sums() {
+        let k = 5;
+        let gadget = |inputs: &[u64], outputs: &[u64]| EqualSums {
+            inputs: known(inputs),
+            outputs: known(outputs),
+        };
+
+        run(k, gadget(&[10, 5], &[7, 8]), vec![]).assert_satisfied();
+        assert!(run(k, gadget(&[10, 5], &[7, 7]), vec![]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct Round {
+        value: Value<Fp>,
+    }
+
+    impl Gadget for Round {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+            let rounded = chip.round::<8, 4>(layouter.namespace(|| "round"), value)?;
+            chip.expose_public(layouter.namespace(|| "expose rounded"), rounded, 0)
+        }
+    }
+
+    #[test]
+    fn round() {
+        let k = 5;
+        let gadget = |value: u64| Round { value: Value::known(Fp::from(value)) };
+
+        // Values carry 4 fractional bits: 2.25 rounds down, 2.75 up, and the
+        // exact half 2.5 rounds up.
+        for (value, rounded) in [(36, 2), (44, 3), (40, 3), (32, 2)] {
+            run(k, gadget(value), vec![Fp::from(rounded)]).assert_satisfied();
+            assert!(run(k, gadget(value), vec![Fp::from(rounded + 1)]).verify().is_err());
+        }
+    }
+
+    #[derive(Default)]
+    struct TwosComplement {
+        value: Value<Fp>,
+    }
+
+    impl Gadget for TwosComplement {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+            let bits = chip.to_twos_complement::<8>(layouter.namespace(|| "twos complement"), value)?;
+            for (row, bit) in bits.into_iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "expose bit"), bit, row)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn to_twos_complement() {
+        let k = 6;
+        let gadget = |value: i64| TwosComplement { value: Value::known(signed(value)) };
+        let pattern = |bits: u8| (0..8).map(|i| Fp::from(((bits >> i) & 1) as u64)).collect();
+
+        for (value, bits) in [
+            (-1, 0b11111111),
+            (1, 0b00000001),
+            (-128, 0b10000000),
+            (127, 0b01111111),
+            (0, 0),
+        ] {
+            run(k, gadget(value), pattern(bits)).assert_satisfied();
+        }
+
+        assert!(run(k, gadget(-1), pattern(0b01111111)).verify().is_err());
+        assert!(run(k, gadget(128), pattern(0b10000000)).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct Divisible {
+        value: Value<Fp>,
+        divisor: Value<Fp>,
+    }
+
+    impl Gadget for Divisible {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+            let divisor = chip.load_private(layouter.namespace(|| "load divisor"), self.divisor)?;
+            chip.assert_divisible::<8>(layouter.namespace(|| "divisible"), value, divisor)
+        }
+    }
+
+    #[test]
+    fn assert_divisible() {
+        let k = 5;
+        let gadget = |value: u64, divisor: u64| Divisible {
+            value: Value::known(Fp::from(value)),
+            divisor: Value::known(Fp::from(divisor)),
+        };
+
+        run(k, gadget(42, 7), vec![]).assert_satisfied();
+        run(k, gadget(0, 5), vec![]).assert_satisfied();
+        assert!(run(k, gadget(43, 7), vec![]).verify().is_err());
+        assert!(run(k, gadget(5, 0), vec![]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct Transpose {
+        a: Vec<Vec<Value<Fp>>>,
+        b: Vec<Vec<Value<Fp>>>,
+    }
+
+    impl Gadget for Transpose {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let a = self
+                .a
+                .iter()
+                .map(|row| load_all(chip, &mut layouter, row))
+                .collect::<Result<Vec<_>, _>>()?;
+            let b = self
+                .b
+                .iter()
+                .map(|row| load_all(chip, &mut layouter, row))
+                .collect::<Result<Vec<_>, _>>()?;
+            chip.assert_transpose(layouter.namespace(|| "transpose"), &a, &b)
+        }
+    }
+
+    #[test]
+    fn assert_transpose() {
+        let k = 5;
+        let gadget = |a: &[&[u64]], b: &[&[u64]]| Transpose {
+            a: a.iter().map(|row| known(row)).collect(),
+            b: b.iter().map(|row| known(row)).collect(),
+        };
+
+        let a: &[&[u64]] = &[&[1, 2, 3], &[4, 5, 6]];
+        run(k, gadget(a, &[&[1, 4], &[2, 5], &[3, 6]]), vec![]).assert_satisfied();
+        assert!(run(k, gadget(a, &[&[1, 4], &[5, 2], &[3, 6]]), vec![]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct FloorDivPow2 {
+        value: Value<Fp>,
+    }
+
+    impl Gadget for FloorDivPow2 {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+            let high = chip.floor_div_pow2::<8, 2>(layouter.namespace(|| "value / 4"), value.clone())?;
+
+            let scaled = chip.mul_by_constant(layouter.namespace(|| "4 * high"), high.clone(), Fp::from(4))?;
+            let low = chip.sub(layouter.namespace(|| "value - 4 * high"), value, scaled)?;
+
+            chip.expose_public(layouter.namespace(|| "expose high"), high, 0)?;
+            chip.expose_public(layouter.namespace(|| "expose low"), low, 1)
+        }
+    }
+
+    #[test]
+    fn floor_div_pow2() {
+        let k = 5;
+        let gadget = |value: u64| FloorDivPow2 {
+            value: Value::known(Fp::from(value)),
+        };
+
+        run(k, gadget(13), vec![Fp::from(3), Fp::from(1)]).assert_satisfied();
+        run(k, gadget(3), vec![Fp::from(0), Fp::from(3)]).assert_satisfied();
+        run(k, gadget(255), vec![Fp::from(63), Fp::from(3)]).assert_satisfied();
+        assert!(run(k, gadget(13), vec![Fp::from(2), Fp::from(5)]).verify().is_err());
+
+        // 13 = 4 * (13 / 4) + 0 in the field, but 13 / 4 is no 6-bit quotient.
+        let four_inv = Fp::from(4).invert().unwrap();
+        run(k, ForgedFloorDivPow2 { high: Fp::from(3) }, vec![]).assert_satisfied();
+        assert!(run(k, ForgedFloorDivPow2 { high: Fp::from(13) * four_inv }, vec![]).verify().is_err());
+    }
+
+    /// Splits 13 into 2 low bits and a chosen high part.
+    #[derive(Default)]
+    struct ForgedFloorDivPow2 {
+        high: Fp,
+    }
+
+    impl Gadget for ForgedFloorDivPow2 {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_constant(layouter.namespace(|| "13"), Fp::from(13))?;
+            let high = Value::known(self.high);
+            chip.split_low_bits(layouter.namespace(|| "value / 4"), value, 2, 6, high)?;
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct ArithmeticProgression {
+        xs: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for ArithmeticProgression {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let xs = load_all(chip, &mut layouter, &self.xs)?;
+            let diff = chip.assert_arithmetic_progression(layouter.namespace(|| "progression"), &xs)?;
+            chip.expose_public(layouter.namespace(|| "expose difference"), diff, 0)
+        }
+    }
+
+    #[test]
+    fn assert_arithmetic_progression() {
+        let k = 5;
+        let gadget = |xs: &[u64]| ArithmeticProgression { xs: known(xs) };
+
+        run(k, gadget(&[2, 5, 8, 11]), vec![Fp::from(3)]).assert_satisfied();
+        assert!(run(k, gadget(&[2, 5, 8, 11]), vec![Fp::from(4)]).verify().is_err());
+        assert!(run(k, gadget(&[2, 5, 9]), vec![Fp::from(3)]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct GeometricProgression {
+        xs: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for GeometricProgression {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let xs = load_all(chip, &mut layouter, &self.xs)?;
+            let ratio = chip.assert_geometric_progression(layouter.namespace(|| "progression"), &xs)?;
+            chip.expose_public(layouter.namespace(|| "expose ratio"), ratio, 0)
+        }
+    }
+
+    #[test]
+    fn assert_geometric_progression() {
+        let k = 5;
+        let gadget = |xs: &[u64]| GeometricProgression { xs: known(xs) };
+
+        run(k, gadget(&[2, 6, 18]), vec![Fp::from(3)]).assert_satisfied();
+        assert!(run(k, gadget(&[2, 6, 18]), vec![Fp::from(2)]).verify().is_err());
+        assert!(run(k, gadget(&[2, 6, 17]), vec![Fp::from(3)]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct RealRoots {
+        coeffs: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for RealRoots {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let coeffs = load_all(chip, &mut layouter, &self.coeffs)?;
+            let [a, b, c] = <[_; 3]>::try_from(coeffs).map_err(|_| Error::Synthesis)?;
+            let flag = chip.has_real_roots(layouter.namespace(|| "has roots"), a, b, c)?;
+            chip.expose_public(layouter.namespace(|| "expose flag"), flag, 0)
+        }
+    }
+
+    #[test]
+    fn has_real_roots() {
+        let k = 6;
+        let gadget = |coeffs: [Fp; 3]| RealRoots {
+            coeffs: coeffs.into_iter().map(Value::known).collect(),
+        };
+
+        // x^2 - 5x + 6 has discriminant 1.
+        run(k, gadget([Fp::one(), signed(-5), Fp::from(6)]), vec![Fp::one()]).assert_satisfied();
+
+        // x^2 + 2x + 1 has discriminant 0, a double root.
+        let double_root = [1, 2, 1].map(Fp::from);
+        run(k, gadget(double_root), vec![Fp::one()]).assert_satisfied();
+        assert!(run(k, gadget(double_root), vec![Fp::zero()]).verify().is_err());
+
+        // x^2 - g for the non-residue g has discriminant 4g, which is not a square.
+        let no_roots = [Fp::one(), Fp::zero(), -Fp::multiplicative_generator()];
+        run(k, gadget(no_roots), vec![Fp::zero()]).assert_satisfied();
+        assert!(run(k, gadget(no_roots), vec![Fp::one()]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct PerfectSquare {
+        n: Value<Fp>,
+    }
+
+    impl Gadget for PerfectSquare {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let n = chip.load_private(layouter.namespace(|| "load n"), self.n)?;
+            let flag = chip.assert_perfect_square::<8>(layouter.namespace(|| "perfect square"), n)?;
+            chip.expose_public(layouter.namespace(|| "expose flag"), flag, 0)
+        }
+    }
+
+    #[test]
+    fn assert_perfect_square() {
+        let k = 6;
+        let gadget = |n: u64| PerfectSquare {
+            n: Value::known(Fp::from(n)),
+        };
+
+        for (n, flag) in [(16, 1), (17, 0), (0, 1), (1, 1), (24, 0), (225, 1)] {
+            run(k, gadget(n), vec![Fp::from(flag)]).assert_satisfied();
+        }
+
+        assert!(run(k, gadget(16), vec![Fp::zero()]).verify().is_err());
+        assert!(run(k, gadget(17), vec![Fp::one()]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct WithinStdDevs {
+        inputs: Vec<Value<Fp>>,
+        sigmas: u64,
+    }
+
+    impl Gadget for WithinStdDevs {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let inputs = load_all(chip, &mut layouter, &self.inputs)?;
+            let [sample, mean, variance] = <[_; 3]>::try_from(inputs).map_err(|_| Error::Synthesis)?;
+            let layouter = layouter.namespace(|| "within n sigma");
+            chip.assert_within_std_devs::<16>(layouter, sample, mean, variance, self.sigmas)
+        }
+    }
+
+    #[test]
+    fn assert_within_std_devs() {
+        let k = 6;
+        let gadget = |sample: u64, mean: u64, variance: u64| WithinStdDevs {
+            inputs: known(&[sample, mean, variance]),
+            sigmas: 2,
+        };
+
+        // Mean 100 with standard deviation 5: two sigmas span [90, 110].
+        for sample in [100, 104, 90, 110] {
+            run(k, gadget(sample, 100, 25), vec![]).assert_satisfied();
+        }
+        for sample in [89, 111, 200] {
+            assert!(run(k, gadget(sample, 100, 25), vec![]).verify().is_err());
+        }
+
+        // n^2 overflows a u64, and a zero variance still leaves no room.
+        let wide = |sample: u64| WithinStdDevs {
+            inputs: known(&[sample, 100, 0]),
+            sigmas: 1 << 32,
+        };
+        run(k, wide(100), vec![]).assert_satisfied();
+        assert!(run(k, wide(101), vec![]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct BitsEqualPublic {
+        value: Value<Fp>,
+    }
+
+    impl Gadget for BitsEqualPublic {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+            chip.expose_public(layouter.namespace(|| "expose value"), value.clone(), 0)?;
+            chip.assert_bits_equal_public::<4>(layouter.namespace(|| "bits"), value, 1)
+        }
+    }
+
+    #[test]
+    fn assert_bits_equal_public() {
+        let k = 5;
+        let gadget = || BitsEqualPublic {
+            value: Value::known(Fp::from(13)),
+        };
+        let public = |bits: [u64; 4]| std::iter::once(13).chain(bits).map(Fp::from).collect();
+
+        run(k, gadget(), public([1, 0, 1, 1])).assert_satisfied();
+        assert!(run(k, gadget(), public([1, 1, 0, 1])).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct SubSaturating {
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Gadget for SubSaturating {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let a = chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+            let diff = chip.sub_saturating::<8>(layouter.namespace(|| "a monus b"), a, b)?;
+            chip.expose_public(layouter.namespace(|| "expose difference"), diff, 0)
+        }
+    }
+
+    #[test]
+    fn sub_saturating() {
+        let k = 5;
+        let gadget = |a: u64, b: u64| SubSaturating {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        };
+
+        for (a, b, diff) in [(5, 3, 2), (3, 5, 0), (4, 4, 0), (0, 255, 0)] {
+            run(k, gadget(a, b), vec![Fp::from(diff)]).assert_satisfied();
+        }
+        assert!(run(k, gadget(3, 5), vec![signed(-2)]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct DistinctPowerSum {
+        value: Value<Fp>,
+        selected: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for DistinctPowerSum {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+            let selected = load_all(chip, &mut layouter, &self.selected)?;
+            chip.assert_distinct_power_sum::<4>(layouter.namespace(|| "power sum"), value, &selected)
+        }
+    }
+
+    #[test]
+    fn assert_distinct_power_sum() {
+        let k = 5;
+        let gadget = |value: u64, selected: &[u64]| DistinctPowerSum {
+            value: Value::known(Fp::from(value)),
+            selected: known(selected),
+        };
+
+        run(k, gadget(5, &[1, 0, 1]), vec![]).assert_satisfied();
+        assert!(run(k, gadget(6, &[1, 0, 1]), vec![]).verify().is_err());
+        // 1 + 2 * 2 also sums to 5, but 2 is not a valid selector.
+        assert!(run(k, gadget(5, &[1, 2, 0]), vec![]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct LagrangeAtPublicPoint {
+        points: Vec<(Fp, Fp)>,
+    }
+
+    impl Gadget for LagrangeAtPublicPoint {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let x = chip.load_instance(layouter.namespace(|| "load x"), 0)?;
+            let y = chip.lagrange_eval(layouter.namespace(|| "interpolate"), &self.points, x)?;
+            chip.expose_public(layouter.namespace(|| "expose y"), y, 1)
+        }
+    }
+
+    #[test]
+    fn lagrange_eval_at_public_point() {
+        let k = 6;
+        // The points lie on p(x) = x^2 + 1.
+        let gadget = || LagrangeAtPublicPoint {
+            points: [(0, 1), (1, 2), (2, 5)].map(|(x, y)| (Fp::from(x), Fp::from(y))).to_vec(),
+        };
+
+        for x in [0, 1, 2, 3, 10] {
+            run(k, gadget(), vec![Fp::from(x), Fp::from(x * x + 1)]).assert_satisfied();
+        }
+        assert!(run(k, gadget(), vec![Fp::from(3), Fp::from(9)]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct Palindrome {
+        value: Value<Fp>,
+    }
+
+    impl Gadget for Palindrome {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+            let flag = chip.is_palindrome::<3>(layouter.namespace(|| "palindrome"), value, 10)?;
+            chip.expose_public(layouter.namespace(|| "expose flag"), flag, 0)
+        }
+    }
+
+    #[test]
+    fn is_palindrome() {
+        let k = 7;
+        let gadget = |value: u64| Palindrome {
+            value: Value::known(Fp::from(value)),
+        };
+
+        for (value, flag) in [(121, 1), (123, 0), (909, 1), (110, 0), (5, 0), (0, 1)] {
+            run(k, gadget(value), vec![Fp::from(flag)]).assert_satisfied();
+        }
+        assert!(run(k, gadget(123), vec![Fp::one()]).verify().is_err());
+        // 1221 has four digits.
+        assert!(run(k, gadget(1221), vec![Fp::one()]).verify().is_err());
+    }
+
+    // Slope 1 below 10, slope 2 on [10, 20) and constant 30 from 20 on.
+    const BREAKPOINTS: [i64; 2] = [10, 20];
+    const SLOPES: [i64; 3] = [1, 2, 0];
+    const INTERCEPTS: [i64; 3] = [0, -10, 30];
+
+    #[derive(Default)]
+    struct PiecewiseLinear {
+        x: Value<Fp>,
+    }
+
+    impl Gadget for PiecewiseLinear {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let x = chip.load_private(layouter.namespace(|| "load x"), self.x)?;
+            let y = chip.piecewise_linear::<8>(
+                layouter.namespace(|| "piecewise linear"),
+                x,
+                &BREAKPOINTS.map(signed),
+                &SLOPES.map(signed),
+                &INTERCEPTS.map(signed),
+            )?;
+            chip.expose_public(layouter.namespace(|| "expose y"), y, 0)
+        }
+    }
+
+    #[test]
+    fn piecewise_linear() {
+        let k = 7;
+        let gadget = |x: u64| PiecewiseLinear {
+            x: Value::known(Fp::from(x)),
+        };
+        let reference = |x: i64| {
+            let segment = BREAKPOINTS.iter().filter(|&&breakpoint| x >= breakpoint).count();
+            SLOPES[segment] * x + INTERCEPTS[segment]
+        };
+
+        for x in [0, 5, 9, 10, 15, 19, 20, 25, 255] {
+            run(k, gadget(x), vec![signed(reference(x as i64))]).assert_satisfied();
+        }
+        assert!(run(k, gadget(15), vec![Fp::from(15)]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct Coprime {
+        a: Value<Fp>,
+        n: u64,
+    }
+
+    impl Gadget for Coprime {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let a = chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            chip.assert_coprime::<8>(layouter.namespace(|| "gcd(a, n) == 1"), a, Fp::from(self.n))
+        }
+    }
+
+    #[test]
+    fn assert_coprime() {
+        let k = 6;
+        let gadget = |a: u64, n: u64| Coprime {
+            a: Value::known(Fp::from(a)),
+            n,
+        };
+
+        for (a, n) in [(15, 8), (7, 13), (1, 200)] {
+            run(k, gadget(a, n), vec![]).assert_satisfied();
+        }
+        // No Bezout coefficients exist for gcd(15, 6) == 3.
+        for (a, n) in [(15, 6), (0, 8), (12, 18)] {
+            assert!(run(k, gadget(a, n), vec![]).verify().is_err());
+        }
+    }
+
+    #[derive(Default)]
+    struct RunningMax {
+        xs: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for RunningMax {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let xs = load_all(chip, &mut layouter, &self.xs)?;
+            let maxima = chip.running_max::<8>(layouter.namespace(|| "running max"), &xs)?;
+            for (row, max) in maxima.into_iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "expose max"), max, row)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn running_max() {
+        let k = 7;
+        let gadget = |xs: &[u64]| RunningMax { xs: known(xs) };
+        let public = |maxima: &[u64]| maxima.iter().map(|m| Fp::from(*m)).collect();
+
+        run(k, gadget(&[3, 1, 4, 1, 5]), public(&[3, 3, 4, 4, 5])).assert_satisfied();
+        run(k, gadget(&[9, 9, 2]), public(&[9, 9, 9])).assert_satisfied();
+        assert!(run(k, gadget(&[3, 1, 4, 1, 5]), public(&[3, 1, 4, 1, 5])).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct Binomial {
+        n: u64,
+        k: u64,
+        claimed: Value<Fp>,
+    }
+
+    impl Gadget for Binomial {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let claimed = chip.load_private(layouter.namespace(|| "load claim"), self.claimed)?;
+            chip.assert_binomial(layouter.namespace(|| "C(n, k)"), self.n, self.k, claimed)
+        }
+    }
+
+    #[test]
+    fn assert_binomial() {
+        let k = 7;
+        let gadget = |n: u64, k: u64, claimed: u64| Binomial {
+            n,
+            k,
+            claimed: Value::known(Fp::from(claimed)),
+        };
+
+        for (n, choose, claimed) in [(5, 2, 10), (5, 0, 1), (5, 5, 1), (10, 3, 120)] {
+            run(k, gadget(n, choose, claimed), vec![]).assert_satisfied();
+        }
+        assert!(run(k, gadget(5, 2, 11), vec![]).verify().is_err());
+        assert!(run(k, gadget(5, 2, 20), vec![]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct ValidateBounds {
+        values: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for ValidateBounds {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            // An age, a month and a percentage score.
+            let bounds = [(18, 130), (1, 13), (0, 101)];
+            let values = load_all(chip, &mut layouter, &self.values)?
+                .into_iter()
+                .zip(bounds)
+                .map(|(value, (min, max))| (value, Fp::from(min), Fp::from(max)))
+                .collect::<Vec<_>>();
+            chip.validate_bounds::<8>(layouter.namespace(|| "validate"), &values)
+        }
+    }
+
+    #[test]
+    fn validate_bounds() {
+        let k = 7;
+        let gadget = |values: [u64; 3]| ValidateBounds { values: known(&values) };
+
+        for values in [[30, 6, 75], [18, 1, 0], [129, 12, 100]] {
+            run(k, gadget(values), vec![]).assert_satisfied();
+        }
+        for values in [[17, 6, 75], [30, 13, 75], [30, 0, 75], [30, 6, 101]] {
+            assert!(run(k, gadget(values), vec![]).verify().is_err());
+        }
+    }
+
+    #[derive(Default)]
+    struct ModSqrt {
+        y: Value<Fp>,
+        x: Value<Fp>,
+    }
+
+    impl Gadget for ModSqrt {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let y = chip.load_private(layouter.namespace(|| "load y"), self.y)?;
+            let x = chip.load_private(layouter.namespace(|| "load x"), self.x)?;
+            chip.assert_mod_sqrt::<8>(layouter.namespace(|| "x^2 == y mod 7"), y, x, Fp::from(7))
+        }
+    }
+
+    #[test]
+    fn assert_mod_sqrt() {
+        let k = 7;
+        let gadget = |y: u64, x: u64| ModSqrt {
+            y: Value::known(Fp::from(y)),
+            x: Value::known(Fp::from(x)),
+        };
+
+        // 3^2 = 9 and 4^2 = 16 are both 2 mod 7.
+        for (y, x) in [(2, 3), (2, 4), (9, 3), (0, 0)] {
+            run(k, gadget(y, x), vec![]).assert_satisfied();
+        }
+        for (y, x) in [(2, 2), (3, 3)] {
+            assert!(run(k, gadget(y, x), vec![]).verify().is_err());
+        }
+    }
+
+    #[derive(Default)]
+    struct LowestTerms {
+        num: Value<Fp>,
+        den: Value<Fp>,
+    }
+
+    impl Gadget for LowestTerms {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let num = chip.load_private(layouter.namespace(|| "load num"), self.num)?;
+            let den = chip.load_private(layouter.namespace(|| "load den"), self.den)?;
+            chip.assert_lowest_terms::<8>(layouter.namespace(|| "lowest terms"), num, den)
+        }
+    }
+
+    #[test]
+    fn assert_lowest_terms() {
+        let k = 7;
+        let gadget = |num: u64, den: u64| LowestTerms {
+            num: Value::known(Fp::from(num)),
+            den: Value::known(Fp::from(den)),
+        };
+
+        for (num, den) in [(3, 4), (1, 2), (7, 5), (22, 7)] {
+            run(k, gadget(num, den), vec![]).assert_satisfied();
+        }
+        // A zero denominator is rejected rather than panicking in witness generation.
+        for (num, den) in [(2, 4), (6, 9), (0, 5), (3, 0)] {
+            assert!(run(k, gadget(num, den), vec![]).verify().is_err());
+        }
+    }
+
+    #[derive(Default)]
+    struct KthSmallest {
+        xs: Vec<Value<Fp>>,
+        k: usize,
+        claimed: Value<Fp>,
+    }
+
+    impl Gadget for KthSmallest {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let xs = load_all(chip, &mut layouter, &self.xs)?;
+            let claimed = chip.load_private(layouter.namespace(|| "load claim"), self.claimed)?;
+            chip.assert_kth_smallest::<8>(layouter.namespace(|| "k-th smallest"), &xs, self.k, claimed)
+        }
+    }
+
+    #[test]
+    fn assert_kth_smallest() {
+        let k = 7;
+        let gadget = |k: usize, claimed: u64| KthSmallest {
+            xs: known(&[5, 1, 4, 2]),
+            k,
+            claimed: Value::known(Fp::from(claimed)),
+        };
+
+        // k = 1 asks for the second smallest.
+        for (rank, claimed) in [(1, 2), (0, 1), (3, 5)] {
+            run(k, gadget(rank, claimed), vec![]).assert_satisfied();
+        }
+        assert!(run(k, gadget(1, 4), vec![]).verify().is_err());
+        // Two elements are less than 3, but 3 is not in the set.
+        assert!(run(k, gadget(2, 3), vec![]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct NonnegativeBalance {
+        deltas: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for NonnegativeBalance {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let deltas = load_all(chip, &mut layouter, &self.deltas)?;
+            let balance = chip.assert_nonnegative_balance::<16>(layouter.namespace(|| "balance"), &deltas)?;
+            chip.expose_public(layouter.namespace(|| "expose balance"), balance, 0)
+        }
+    }
+
+    #[test]
+    fn assert_nonnegative_balance() {
+        let k = 8;
+        let gadget = |deltas: &[i64]| NonnegativeBalance {
+            deltas: deltas.iter().map(|d| Value::known(signed(*d))).collect(),
+        };
+
+        run(k, gadget(&[100, -30, -70, 50, -20]), vec![Fp::from(30)]).assert_satisfied();
+        run(k, gadget(&[]), vec![Fp::zero()]).assert_satisfied();
+        assert!(run(k, gadget(&[100, -30, -70, 50, -20]), vec![Fp::from(31)]).verify().is_err());
+        // The balance dips to -10 before recovering to 40.
+        assert!(run(k, gadget(&[100, -110, 50]), vec![Fp::from(40)]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct PackBytes {
+        bytes: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for PackBytes {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let bytes = load_all(chip, &mut layouter, &self.bytes)?;
+            let packed = chip.pack_bytes::<2>(layouter.namespace(|| "pack"), &bytes)?;
+
+            // Unpacking recovers the bytes, least significant first.
+            let unpacked = chip.decompose_base(layouter.namespace(|| "unpack"), packed.clone(), 2, 256)?;
+            for (byte, unpacked) in bytes.iter().rev().zip(&unpacked) {
+                chip.constrain_equal(layouter.namespace(|| "byte == unpacked"), byte, unpacked)?;
+            }
+
+            chip.expose_public(layouter.namespace(|| "expose packed"), packed, 0)
+        }
+    }
+
+    #[test]
+    fn pack_bytes() {
+        let k = 7;
+        let gadget = |bytes: &[u64]| PackBytes { bytes: known(bytes) };
+
+        run(k, gadget(&[0x41, 0x42]), vec![Fp::from(0x4142)]).assert_satisfied();
+        run(k, gadget(&[0xff, 0x00]), vec![Fp::from(0xff00)]).assert_satisfied();
+        assert!(run(k, gadget(&[0x41, 0x42]), vec![Fp::from(0x4241)]).verify().is_err());
+        // 0x100 is not a byte, even though 0x41 * 256 + 0x100 fits in two bytes.
+        assert!(run(k, gadget(&[0x41, 0x100]), vec![Fp::from(0x4200)]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct WindowedSum {
+        xs: Vec<Value<Fp>>,
+        window: usize,
+    }
+
+    impl Gadget for WindowedSum {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let xs = load_all(chip, &mut layouter, &self.xs)?;
+            let sums = chip.windowed_sum(layouter.namespace(|| "windowed sum"), &xs, self.window)?;
+            for (row, sum) in sums.into_iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "expose sum"), sum, row)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn windowed_sum() {
+        let k = 5;
+        let gadget = |window: usize| WindowedSum {
+            xs: known(&[1, 2, 3, 4]),
+            window,
+        };
+        let public = |sums: &[u64]| sums.iter().map(|s| Fp::from(*s)).collect();
+
+        run(k, gadget(2), public(&[3, 5, 7])).assert_satisfied();
+        run(k, gadget(1), public(&[1, 2, 3, 4])).assert_satisfied();
+        run(k, gadget(4), public(&[10])).assert_satisfied();
+        assert!(run(k, gadget(2), public(&[3, 5, 8])).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct Canonical {
+        digits: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for Canonical {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let digits = load_all(chip, &mut layouter, &self.digits)?;
+            chip.assert_canonical(layouter.namespace(|| "canonical"), &digits)
+        }
+    }
+
+    #[test]
+    fn assert_canonical() {
+        let k = 5;
+        let gadget = |digits: &[u64]| Canonical { digits: known(digits) };
+
+        for digits in [&[1, 2, 3][..], &[9, 0, 0], &[0, 0, 0], &[0]] {
+            run(k, gadget(digits), vec![]).assert_satisfied();
+        }
+        for digits in [&[0, 0, 1][..], &[0, 2, 3]] {
+            assert!(run(k, gadget(digits), vec![]).verify().is_err());
+        }
+    }
+
+    #[derive(Default)]
+    struct NoOverlap {
+        bounds: Vec<Value<Fp>>,
+    }
 
-    fn load_constant(&self, layouter: impl Layouter<F>, constant: F) -> Result<Self::Num, Error>;
+    impl Gadget for NoOverlap {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let [a_start, a_end, b_start, b_end]: [_; 4] =
+                load_all(chip, &mut layouter, &self.bounds)?.try_into().map_err(|_| Error::Synthesis)?;
+            chip.assert_no_overlap::<8>(layouter.namespace(|| "no overlap"), a_start, a_end, b_start, b_end)
+        }
+    }
 
-    fn mul(
-        &self,
-        layouter: impl Layouter<F>,
-        a: Self::Num,
-        b: Self::Num,
-    ) -> Result<Self::Num, Error>;
+    #[test]
+    fn assert_no_overlap() {
+        let k = 6;
+        let gadget = |a: (u64, u64), b: (u64, u64)| NoOverlap {
+            bounds: known(&[a.0, a.1, b.0, b.1]),
+        };
 
-    fn expose_public(
-        &self,
-        layouter: impl Layouter<F>,
-        num: Self::Num,
-        row: usize,
-    ) -> Result<(), Error>;
-}
+        // Disjoint and adjacent, in either order.
+        for (a, b) in [((1, 5), (7, 9)), ((7, 9), (1, 5)), ((1, 5), (5, 9)), ((5, 9), (1, 5))] {
+            run(k, gadget(a, b), vec![]).assert_satisfied();
+        }
+        // Partial overlap, containment and equal intervals.
+        for (a, b) in [((1, 6), (5, 9)), ((5, 9), (1, 6)), ((1, 9), (3, 4)), ((2, 4), (2, 4))] {
+            assert!(run(k, gadget(a, b), vec![]).verify().is_err());
+        }
+    }
 
-struct FieldChip<F: FieldExt> {
-    config: FieldConfig,
-    _marker: PhantomData<F>,
-}
+    #[derive(Default)]
+    struct PowConst {
+        x: Value<Fp>,
+        exp: u64,
+    }
 
-#[derive(Clone, Debug)]
-struct FieldConfig {
-    advice: [Column<Advice>; 2],
-    instance: Column<Instance>,
-    selector: Selector,
-}
+    impl Gadget for PowConst {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let x = chip.load_private(layouter.namespace(|| "load x"), self.x)?;
+            let power = chip.pow_const(layouter.namespace(|| "x^exp"), x, self.exp)?;
+            chip.expose_public(layouter.namespace(|| "expose power"), power, 0)
+        }
+    }
+
+    #[test]
+    fn pow_const() {
+        let k = 5;
+        let gadget = |x: u64, exp: u64| PowConst {
+            x: Value::known(Fp::from(x)),
+            exp,
+        };
 
+        // 13 = 0b1101 takes three squarings and two extra multiplications.
+        run(k, gadget(3, 13), vec![Fp::from(1_594_323)]).assert_satisfied();
+        assert!(run(k, gadget(3, 13), vec![Fp::from(1_594_322)]).verify().is_err());
 
-impl<F: FieldExt> FieldChip<F> {
-    fn construct(config: <Self as Chip<F>>::Config) -> Self {
-        Self { 
-            config, 
-            _marker: PhantomData,
+        for exp in [0, 1, 2, 5, 16] {
+            run(k, gadget(3, exp), vec![Fp::from(3u64.pow(exp as u32))]).assert_satisfied();
         }
+        assert!(run(k, gadget(3, 5), vec![Fp::from(81)]).verify().is_err());
     }
 
-    fn configure(
-        meta: &mut ConstraintSystem<F>,
-        advice: [Column<Advice>; 2],
-        instance: Column<Instance>,
-        constant: Column<Fixed>,
-    ) -> <Self as Chip<F>>::Config {
-        meta.enable_equality(instance);
-        meta.enable_constant(constant);
-        for column in &advice {
-            meta.enable_equality(*column);
+    #[derive(Default)]
+    struct NotDivisible {
+        value: Value<Fp>,
+        divisor: Value<Fp>,
+    }
+
+    impl Gadget for NotDivisible {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+            let divisor = chip.load_private(layouter.namespace(|| "load divisor"), self.divisor)?;
+            chip.assert_not_divisible::<8>(layouter.namespace(|| "not divisible"), value, divisor)
         }
-        let selector = meta.selector();
+    }
 
-        meta.create_gate("mul", |meta| {
-            let lhs = meta.query_advice(advice[0], Rotation::cur());
-            let rhs = meta.query_advice(advice[1], Rotation::cur());
-            let out = meta.query_advice(advice[0], Rotation::next());
-            let selector = meta.query_selector(selector);
-            vec![selector * (lhs * rhs - out)]
-        });
+    #[test]
+    fn assert_not_divisible() {
+        let k = 6;
+        let gadget = |value: u64, divisor: u64| NotDivisible {
+            value: Value::known(Fp::from(value)),
+            divisor: Value::known(Fp::from(divisor)),
+        };
 
-        FieldConfig {
-            advice,
-            instance,
-            selector,
+        run(k, gadget(43, 7), vec![]).assert_satisfied();
+        run(k, gadget(3, 7), vec![]).assert_satisfied();
+        assert!(run(k, gadget(42, 7), vec![]).verify().is_err());
+        assert!(run(k, gadget(0, 5), vec![]).verify().is_err());
+        assert!(run(k, gadget(5, 0), vec![]).verify().is_err());
+    }
+
+    #[derive(Default)]
+    struct PrimePowerExponent {
+        n: Value<Fp>,
+        p: u64,
+        e: u64,
+    }
+
+    impl Gadget for PrimePowerExponent {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let n = chip.load_private(layouter.namespace(|| "load n"), self.n)?;
+            let p = Fp::from(self.p);
+            chip.assert_prime_power_exponent::<8>(layouter.namespace(|| "exponent"), n, p, self.e)
         }
     }
-}
 
-impl<F: FieldExt> Chip<F> for FieldChip<F> {
-    type Config = FieldConfig;
-    type Loaded = ();
-    
-    fn config(&self) -> &Self::Config {
-        &self.config
+    #[test]
+    fn assert_prime_power_exponent() {
+        let k = 7;
+        let gadget = |p: u64, e: u64| PrimePowerExponent {
+            n: Value::known(Fp::from(12)),
+            p,
+            e,
+        };
+
+        // 12 = 2^2 * 3.
+        run(k, gadget(2, 2), vec![]).assert_satisfied();
+        run(k, gadget(3, 1), vec![]).assert_satisfied();
+        run(k, gadget(5, 0), vec![]).assert_satisfied();
+        assert!(run(k, gadget(2, 3), vec![]).verify().is_err());
+        assert!(run(k, gadget(2, 1), vec![]).verify().is_err());
     }
 
-    fn loaded(&self) -> &Self::Loaded {
-        &()
+    #[derive(Default)]
+    struct OneOf {
+        value: Value<Fp>,
     }
-}
 
-#[derive(Clone)]
-struct Number<F: FieldExt>(AssignedCell<F, F>);
+    impl Gadget for OneOf {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+            let set = [signed(-1), signed(0), signed(1)];
+            chip.assert_one_of(layouter.namespace(|| "value in set"), value, &set)
+        }
+    }
 
-impl<F: FieldExt> NumericInstructions<F> for FieldChip<F>  {
-    type Num = Number<F>;
+    #[test]
+    fn assert_one_of() {
+        let k = 5;
+        let gadget = |value: i64| OneOf {
+            value: Value::known(signed(value)),
+        };
 
-    fn load_private(&self, mut layouter: impl Layouter<F>, value: Value<F>) -> Result<Self::Num, Error> {
-        let config = self.config();
+        for value in [-1, 0, 1] {
+            run(k, gadget(value), vec![]).assert_satisfied();
+        }
+        for value in [2, -2, 3] {
+            assert!(run(k, gadget(value), vec![]).verify().is_err());
+        }
+    }
 
-        layouter.assign_region(
-            || "load private",
-            |mut region| {
-                region
-                    .assign_advice(|| "private input", config.advice[0], 0, || value)
-                    .map(Number)
-            },
-        )
+    #[derive(Default)]
+    struct BalancedTernary {
+        value: Value<Fp>,
     }
 
-    fn load_constant(&self, mut layouter: impl Layouter<F>, constant: F) -> Result<Self::Num, Error> {
-        let config = self.config();
+    impl Gadget for BalancedTernary {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+            let digits = chip.to_balanced_ternary::<3>(layouter.namespace(|| "balanced ternary"), value)?;
+            for (row, digit) in digits.into_iter().enumerate() {
+                chip.expose_public(layouter.namespace(|| "expose digit"), digit, row)?;
+            }
+            Ok(())
+        }
+    }
 
-        layouter.assign_region(
-            || "load constant", 
-            |mut region| {
-                region
-                    .assign_advice_from_constant(|| "constant value", config.advice[0], 0, constant)
-                    .map(Number)
-            },
-        )
+    #[test]
+    fn to_balanced_ternary() {
+        let k = 7;
+        let gadget = |value: i64| BalancedTernary {
+            value: Value::known(signed(value)),
+        };
+        let digits = |digits: [i64; 3]| digits.map(signed).to_vec();
+
+        // 5 = 9 - 3 - 1.
+        run(k, gadget(5), digits([-1, -1, 1])).assert_satisfied();
+        run(k, gadget(-5), digits([1, 1, -1])).assert_satisfied();
+        run(k, gadget(13), digits([1, 1, 1])).assert_satisfied();
+        run(k, gadget(0), digits([0, 0, 0])).assert_satisfied();
+
+        // The ordinary ternary digits of 5 recompose it, but 2 is out of the digit set.
+        assert!(run(k, gadget(5), digits([2, 1, 0])).verify().is_err());
+        // 14 does not fit in three balanced-ternary digits.
+        assert!(run(k, gadget(14), digits([-1, -1, -1])).verify().is_err());
     }
 
-    fn mul(
-        &self,
-        mut layouter: impl Layouter<F>,
-        a: Self::Num,
-        b: Self::Num,
-    ) -> Result<Self::Num, Error> {
-        let config = self.config();
+    #[derive(Default)]
+    struct Reciprocal {
+        x: Value<Fp>,
+    }
 
-        layouter.assign_region(
-            || "mul", 
-            |mut region| {
-                config.selector.enable(&mut region, 0)?;
+    impl Gadget for Reciprocal {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let x = chip.load_private(layouter.namespace(|| "load x"), self.x)?;
+            let r = chip.reciprocal::<12, 8>(layouter.namespace(|| "1 / x"), x)?;
+            chip.expose_public(layouter.namespace(|| "expose r"), r, 0)
+        }
+    }
 
-                a.0.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
-                b.0.copy_advice(|| "rhs", &mut region, config.advice[1], 0)?;
+    #[test]
+    fn reciprocal() {
+        let k = 7;
+        let gadget = |x: u64| Reciprocal {
+            x: Value::known(Fp::from(x)),
+        };
 
-                let value = a.0.value().copied() * b.0.value();
+        // 256 / 3 = 85.33, and 3 * 85 = 255 misses 2^8 by less than 3.
+        for (x, r) in [(3, 85), (7, 37), (10, 26), (1, 256), (256, 1)] {
+            assert!((x * r as i64 - 256).abs() < x);
+            run(k, gadget(x as u64), vec![Fp::from(r)]).assert_satisfied();
+        }
 
-                region
-                    .assign_advice(|| "lhs * rhs", config.advice[0], 1, || value)
-                    .map(Number)
-            },
-        )
+        assert!(run(k, gadget(3), vec![Fp::from(84)]).verify().is_err());
+        // Zero has no reciprocal.
+        assert!(run(k, gadget(0), vec![Fp::zero()]).verify().is_err());
     }
 
-    fn expose_public(
-            &self,
-            mut layouter: impl Layouter<F>,
-            num: Self::Num,
-            row: usize,
-    ) -> Result<(), Error> {
-        let config = self.config();
+    #[derive(Default)]
+    struct Div {
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
 
-        layouter.constrain_instance(num.0.cell(), config.instance, row)
+    impl Gadget for Div {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let a = chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+            let quotient = chip.div(layouter.namespace(|| "a / b"), a, b)?;
+            // The quotient stays the fraction a / b until the prover evaluates it.
+            quotient
+                .value()
+                .zip(self.b)
+                .assert_if_known(|(quotient, b)| quotient.denominator() == Some(*b));
+            chip.expose_public(layouter.namespace(|| "expose a / b"), quotient, 0)
+        }
     }
-}
 
+    #[test]
+    fn div() {
+        let k = 4;
+        let gadget = |a: u64, b: u64| Div {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        };
 
-#[derive(Default)]
-struct MyCircuit<F: FieldExt> {
-    constant: F,
-    a: Value<F>,
-    b: Value<F>,
-}
+        run(k, gadget(42, 7), vec![Fp::from(6)]).assert_satisfied();
+        run(k, gadget(0, 7), vec![Fp::zero()]).assert_satisfied();
+        // Division is in the field, so 1 / 2 is the inverse of 2.
+        run(k, gadget(1, 2), vec![Fp::from(2).invert().unwrap()]).assert_satisfied();
 
-impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
-    type Config = FieldConfig;
-    type FloorPlanner = SimpleFloorPlanner;
+        assert!(run(k, gadget(42, 7), vec![Fp::from(7)]).verify().is_err());
+        // Nothing times zero is one.
+        assert!(run(k, gadget(42, 0), vec![Fp::zero()]).verify().is_err());
+        assert!(run(k, gadget(0, 0), vec![Fp::zero()]).verify().is_err());
+    }
 
-    fn without_witnesses(&self) -> Self {
-        Self::default()
+    #[derive(Default)]
+    struct Accessors {
+        a: Value<Fp>,
+        tamper: Fp,
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let advice = [meta.advice_column(), meta.advice_column()];
-        let instance = meta.instance_column();
-        let constant = meta.fixed_column();
-        FieldChip::configure(meta, advice, instance, constant)
+    impl Gadget for Accessors {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let a = chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let square = chip.square(layouter.namespace(|| "a^2"), a)?;
+            square
+                .evaluate()
+                .zip(self.a)
+                .assert_if_known(|(square, a)| *square == a.square());
+
+            // Wire the square into a region of its own, as another chip would.
+            let advice = chip.config.advice[1];
+            let copy = layouter.assign_region(
+                || "copy by cell",
+                |mut region| {
+                    let value = square.value().map(|square| *square + self.tamper);
+                    let copy = region.assign_advice(|| "copy", advice, 0, || value)?;
+                    region.constrain_equal(square.cell(), copy.cell())?;
+                    Ok(Number(copy))
+                },
+            )?;
+            chip.expose_public(layouter.namespace(|| "expose copy"), copy, 0)
+        }
     }
 
-    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
-        let field_chip = FieldChip::<F>::construct(config);
-        let a = field_chip.load_private(layouter.namespace(|| "load a"), self.a)?;
-        let b = field_chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+    #[test]
+    fn number_accessors() {
+        let k = 4;
+        let gadget = |tamper: u64| Accessors {
+            a: Value::known(Fp::from(7)),
+            tamper: Fp::from(tamper),
+        };
 
-        let constant = field_chip.load_constant(layouter.namespace(|| "load constant"), self.constant)?;
-        let ab = field_chip.mul(layouter.namespace(|| "a * b"), a, b)?;
-        let ab_sq = field_chip.mul(layouter.namespace(|| "ab * ab"), ab.clone(), ab)?;
-        let c = field_chip.mul(layouter.namespace(|| "constant * ab_sq"), constant, ab_sq)?;
+        run(k, gadget(0), vec![Fp::from(49)]).assert_satisfied();
+        // The copy is tied to the square's cell, so it cannot drift from it.
+        assert!(run(k, gadget(1), vec![Fp::from(50)]).verify().is_err());
+    }
 
-        field_chip.expose_public(layouter.namespace(|| "expose c"), c, 0)
-        
+    #[derive(Default)]
+    struct AssertEqual {
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Gadget for AssertEqual {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let a = chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+            let a_squared = chip.square(layouter.namespace(|| "a^2"), a)?;
+            chip.assert_equal(layouter.namespace(|| "a^2 == b"), a_squared.clone(), b)?;
+            chip.assert_equal_const(layouter.namespace(|| "a^2 == 36"), a_squared, Fp::from(36))
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use halo2_proofs::{
-        dev::MockProver, 
-        pasta::Fp, 
-        circuit::Value
-    };
-    use super::MyCircuit;
-    
     #[test]
-    fn numeric_instructions() {
+    fn assert_equal() {
+        let k = 4;
+        let gadget = |a: u64, b: u64| AssertEqual {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        };
+        let only_permutation_failures = |prover: MockProver<Fp>| {
+            let failures = prover.verify().unwrap_err();
+            !failures.is_empty() && failures.iter().all(|f| matches!(f, VerifyFailure::Permutation { .. }))
+        };
+
+        run(k, gadget(6, 36), vec![]).assert_satisfied();
+
+        // a^2 != b breaks the copy between them.
+        assert!(only_permutation_failures(run(k, gadget(6, 35), vec![])));
+        // a^2 == b holds, but neither is the constant.
+        assert!(only_permutation_failures(run(k, gadget(5, 25), vec![])));
+    }
+
+    #[derive(Default)]
+    struct Invert {
+        x: Value<Fp>,
+        checked: bool,
+    }
+
+    impl Gadget for Invert {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let x = chip.load_private(layouter.namespace(|| "load x"), self.x)?;
+            // Either way the inverse is witnessed as the fraction 1 / x.
+            let deferred = |x_inv: &Number<Fp>| {
+                x_inv
+                    .value()
+                    .zip(self.x)
+                    .assert_if_known(|(x_inv, x)| x_inv.denominator() == Some(*x));
+            };
+            if self.checked {
+                let (x_inv, is_zero) = chip.invert_checked(layouter.namespace(|| "1 / x"), x)?;
+                deferred(&x_inv);
+                chip.expose_public_many(layouter.namespace(|| "expose"), &[x_inv, is_zero], 0)
+            } else {
+                let x_inv = chip.invert(layouter.namespace(|| "1 / x"), x)?;
+                deferred(&x_inv);
+                chip.expose_public(layouter.namespace(|| "expose x_inv"), x_inv, 0)
+            }
+        }
+    }
 
+    #[test]
+    fn invert() {
         let k = 4;
-        let constant = Fp::from(7);
-        let a = Fp::from(2);
-        let b = Fp::from(3);
-        let c = constant * a.square() * b.square();
+        let gadget = |x: u64, checked: bool| Invert {
+            x: Value::known(Fp::from(x)),
+            checked,
+        };
+        let inv4 = Fp::from(4).invert().unwrap();
 
-        let circuit = MyCircuit {
-            constant,
-            a: Value::known(a),
-            b: Value::known(b),
+        run(k, gadget(4, false), vec![inv4]).assert_satisfied();
+        assert!(run(k, gadget(4, false), vec![Fp::from(4)]).verify().is_err());
+        // Zero has no inverse to claim.
+        assert!(run(k, gadget(0, false), vec![Fp::zero()]).verify().is_err());
+
+        run(k + 1, gadget(4, true), vec![inv4, Fp::zero()]).assert_satisfied();
+        run(k + 1, gadget(0, true), vec![Fp::zero(), Fp::one()]).assert_satisfied();
+        assert!(run(k + 1, gadget(4, true), vec![inv4, Fp::one()]).verify().is_err());
+        assert!(run(k + 1, gadget(0, true), vec![Fp::zero(), Fp::zero()]).verify().is_err());
+    }
+
+    #[test]
+    fn poly_root() {
+        let k = 5;
+        let circuit = |root: i64| PolyRootCircuit {
+            degree: 3,
+            root: Value::known(signed(root)),
         };
+        // (x - 1)(x - 2)(x + 3) = x^3 - 7x + 6.
+        let coeffs = vec![signed(6), signed(-7), signed(0), signed(1)];
 
-        let mut public_input = vec![c];
+        for root in [1, 2, -3] {
+            let prover = MockProver::run(k, &circuit(root), vec![coeffs.clone()]).unwrap();
+            prover.assert_satisfied();
+        }
 
-        let prover = MockProver::run(k, &circuit, vec![public_input.clone()]).unwrap();
+        let prover = MockProver::run(k, &circuit(3), vec![coeffs.clone()]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 3 is a root of a different public polynomial, x^3 - 7x - 6.
+        let mut shifted = coeffs;
+        shifted[0] = signed(-6);
+        let prover = MockProver::run(k, &circuit(3), vec![shifted]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[derive(Default)]
+    struct LinearCombination {
+        values: Vec<Value<Fp>>,
+    }
+
+    impl Gadget for LinearCombination {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let values = load_all(chip, &mut layouter, &self.values)?;
+            // Weights 1, -2, 3, -4, ...
+            let terms = (1..)
+                .zip(values)
+                .map(|(i, x)| (signed(if i % 2 == 0 { -i } else { i }), x))
+                .collect::<Vec<_>>();
+            let sum = chip.linear_combination(layouter.namespace(|| "sum c_i * x_i"), &terms)?;
+            chip.expose_public(layouter.namespace(|| "expose sum"), sum, 0)
+        }
+    }
+
+    #[test]
+    fn linear_combination() {
+        let k = 6;
+        let values = (1..=16).collect::<Vec<u64>>();
+        let gadget = |values: &[u64]| LinearCombination { values: known(values) };
+
+        // sum (-1)^(i+1) * i^2 over 1..=16 = -(16 * 17 / 2) = -136.
+        run(k, gadget(&values), vec![signed(-136)]).assert_satisfied();
+        assert!(run(k, gadget(&values), vec![signed(136)]).verify().is_err());
+        run(k, gadget(&[]), vec![Fp::zero()]).assert_satisfied();
+
+        let mut perturbed = values;
+        perturbed[15] += 1;
+        assert!(run(k, gadget(&perturbed), vec![signed(-136)]).verify().is_err());
+    }
+
+    #[test]
+    fn mul_many_width() {
+        let pairs = [(2, 3), (4, 5), (6, 7), (8, 9), (10, 11), (12, 13)];
+        let known_pairs = pairs
+            .iter()
+            .map(|(a, b)| (Value::known(Fp::from(*a)), Value::known(Fp::from(*b))))
+            .collect::<Vec<_>>();
+        let products = pairs.iter().map(|(a, b)| Fp::from(a * b)).collect::<Vec<_>>();
+
+        // Two columns take a row pair per product, and need twice the rows.
+        let narrow = ProductsCircuit::<Fp, 2> { pairs: known_pairs.clone() };
+        assert!(MockProver::run(4, &narrow, vec![products.clone()]).is_err());
+        MockProver::run(5, &narrow, vec![products.clone()]).unwrap().assert_satisfied();
+
+        // Four columns fit two products per row pair in the smaller circuit.
+        let wide = ProductsCircuit::<Fp, 4> { pairs: known_pairs.clone() };
+        MockProver::run(4, &wide, vec![products.clone()]).unwrap().assert_satisfied();
+
+        let mut wrong = products;
+        wrong[3] += Fp::one();
+        assert!(MockProver::run(4, &wide, vec![wrong]).unwrap().verify().is_err());
+
+        // An odd number of pairs pads the last row pair.
+        let odd = ProductsCircuit::<Fp, 4> { pairs: known_pairs[..3].to_vec() };
+        let prover = MockProver::run(4, &odd, vec![vec![Fp::from(6), Fp::from(20), Fp::from(42)]]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[derive(Default)]
+    struct IsEqual {
+        a: Value<Fp>,
+        b: Value<Fp>,
+    }
+
+    impl Gadget for IsEqual {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let a = chip.load_private(layouter.namespace(|| "load a"), self.a)?;
+            let b = chip.load_private(layouter.namespace(|| "load b"), self.b)?;
+            let flag = chip.is_equal(layouter.namespace(|| "a == b?"), a, b)?;
+            chip.expose_public(layouter.namespace(|| "expose flag"), flag, 0)
+        }
+    }
+
+    #[test]
+    fn is_equal() {
+        let k = 4;
+        let gadget = |a: u64, b: u64| IsEqual {
+            a: Value::known(Fp::from(a)),
+            b: Value::known(Fp::from(b)),
+        };
+
+        run(k, gadget(5, 5), vec![Fp::one()]).assert_satisfied();
+        run(k, gadget(5, 6), vec![Fp::zero()]).assert_satisfied();
+        run(k, gadget(0, 0), vec![Fp::one()]).assert_satisfied();
+        assert!(run(k, gadget(5, 5), vec![Fp::zero()]).verify().is_err());
+        assert!(run(k, gadget(5, 6), vec![Fp::one()]).verify().is_err());
+
+        // Claiming equality for unequal values breaks the is-zero gate itself.
+        assert!(run(k, ForgedIsEqual { inv: Fp::zero(), flag: Fp::one() }, vec![]).verify().is_err());
+        let inv = -Fp::one();
+        assert!(run(k, ForgedIsEqual { inv, flag: Fp::one() }, vec![]).verify().is_err());
+    }
+
+    /// Assigns the is-equal region for 5 and 6 with a chosen inverse and flag.
+    #[derive(Default)]
+    struct ForgedIsEqual {
+        inv: Fp,
+        flag: Fp,
+    }
+
+    impl Gadget for ForgedIsEqual {
+        fn synthesize(&self, chip: &FieldChip<Fp>, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+            let advice = chip.config.advice;
+            layouter.assign_region(
+                || "forged a == b?",
+                |mut region| {
+                    region.assign_advice(|| "a", advice[0], 0, || Value::known(Fp::from(5)))?;
+                    region.assign_advice(|| "b", advice[1], 0, || Value::known(Fp::from(6)))?;
+                    chip.config.s_is_equal.enable(&mut region, 1)?;
+                    region.assign_advice(|| "(a - b) inv", advice[0], 1, || Value::known(self.inv))?;
+                    region.assign_advice(|| "a == b", advice[1], 1, || Value::known(self.flag))?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn sum() {
+        let k = 8;
+        let circuit = |xs: &[u64]| SumCircuit { xs: known(xs) };
+        let xs = (1..=100).collect::<Vec<u64>>();
+
+        let prover = MockProver::run(k, &circuit(&xs), vec![vec![Fp::from(5050)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(&xs), vec![vec![Fp::from(5049)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let prover = MockProver::run(k, &circuit(&[]), vec![vec![Fp::zero()]]).unwrap();
         prover.assert_satisfied();
-        
-        public_input[0] += Fp::one();
-        let prover = MockProver::run(k, &circuit, vec![public_input]).unwrap();
+
+        // Changing one value anywhere in the region moves the total.
+        let mut xs = xs;
+        xs[57] += 1;
+        let prover = MockProver::run(k, &circuit(&xs), vec![vec![Fp::from(5050)]]).unwrap();
         assert!(prover.verify().is_err());
     }
-}
\ No newline at end of file
+}
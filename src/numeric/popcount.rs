@@ -0,0 +1,178 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+use crate::range_check::example2::table::RangeTableConfig;
+
+/// Counts the set bits of a word one byte at a time.
+///
+/// A table maps every byte to its popcount. The word is split into witnessed
+/// bytes, each byte is looked up together with its count, which also range
+/// checks it, and the bytes are recomposed and constrained to the word.
+#[derive(Clone, Debug)]
+struct PopcountConfig<F: FieldExt> {
+    field: FieldConfig<F>,
+    q_lookup: Selector,
+    table: RangeTableConfig<F, 256>,
+    count: TableColumn,
+}
+
+struct PopcountChip<F: FieldExt> {
+    config: PopcountConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> PopcountChip<F> {
+    fn construct(config: PopcountConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>, field: FieldConfig<F>) -> PopcountConfig<F> {
+        let advice = field.advice;
+        let q_lookup = meta.complex_selector();
+        let table = RangeTableConfig::configure(meta);
+        let count = meta.lookup_table_column();
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_lookup);
+            let byte = meta.query_advice(advice[0], Rotation::cur());
+            let byte_count = meta.query_advice(advice[1], Rotation::cur());
+
+            vec![(q.clone() * byte, table.value), (q * byte_count, count)]
+        });
+
+        PopcountConfig {
+            field,
+            q_lookup,
+            table,
+            count,
+        }
+    }
+
+    fn field_chip(&self) -> FieldChip<F> {
+        FieldChip::construct(self.config.field.clone())
+    }
+
+    fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config
+            .table
+            .load_transformed(layouter, self.config.count, |byte| byte.count_ones() as u64)
+    }
+
+    /// Returns the number of set bits of a `BYTES`-byte `word`.
+    fn popcount<const BYTES: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        word: Number<F>,
+    ) -> Result<Number<F>, Error> {
+        let advice = self.config.field.advice;
+        let word_int = word.evaluate().as_ref().map(|word| word.get_lower_128());
+
+        let (bytes, counts): (Vec<_>, Vec<_>) = layouter.assign_region(
+            || "byte popcounts",
+            |mut region| {
+                (0..BYTES)
+                    .map(|row| {
+                        self.config.q_lookup.enable(&mut region, row)?;
+
+                        let byte = word_int.map(|word| (word >> (8 * row)) & 0xFF);
+                        let count = byte.map(|byte| Assigned::from(F::from(byte.count_ones() as u64)));
+                        let byte = byte.map(|byte| Assigned::from(F::from_u128(byte)));
+
+                        let byte = region.assign_advice(|| "byte", advice[0], row, || byte)?;
+                        let count = region.assign_advice(|| "count", advice[1], row, || count)?;
+
+                        Ok((Number(byte), Number(count)))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+                    .map(|pairs| pairs.into_iter().unzip())
+            },
+        )?;
+
+        let field_chip = self.field_chip();
+        let (top, rest) = bytes.split_last().ok_or(Error::Synthesis)?;
+        let mut acc = top.clone();
+        for byte in rest.iter().rev() {
+            acc = field_chip.mul_by_constant(layouter.namespace(|| "acc * 256"), acc, F::from(256))?;
+            acc = field_chip.add(layouter.namespace(|| "acc + byte"), acc, byte.clone())?;
+        }
+        field_chip.constrain_equal(layouter.namespace(|| "word == bytes"), &word, &acc)?;
+
+        field_chip.accumulate(layouter.namespace(|| "sum of counts"), &counts)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct PopcountCircuit<F: FieldExt> {
+    word: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for PopcountCircuit<F> {
+    type Config = PopcountConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let field = FieldChip::configure(meta, advice, instance, constant);
+        PopcountChip::configure(meta, field)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = PopcountChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        chip.load(&mut layouter)?;
+
+        let word = field_chip.load_private(layouter.namespace(|| "load word"), self.word)?;
+        let count = chip.popcount::<2>(layouter.namespace(|| "popcount"), word)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose popcount"), count, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::PopcountCircuit;
+
+    #[test]
+    fn popcount() {
+        let k = 9;
+        let circuit = |word: u64| PopcountCircuit {
+            word: Value::known(Fp::from(word)),
+        };
+
+        for (word, count) in [(0xFF00, 8), (0x1234, 5), (0, 0)] {
+            let prover = MockProver::run(k, &circuit(word), vec![vec![Fp::from(count)]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let prover = MockProver::run(k, &circuit(0xFF00), vec![vec![Fp::from(7)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 0x10000 does not fit in two bytes.
+        let prover = MockProver::run(k, &circuit(0x10000), vec![vec![Fp::from(1)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions};
+
+/// A private inner product: the prover knows two vectors `a` and `b` and shows
+/// that their dot product equals the public scalar at instance row 0, without
+/// revealing either vector.
+#[derive(Default)]
+#[allow(dead_code)]
+struct PrivateDotCircuit<F: FieldExt> {
+    a: Vec<Value<F>>,
+    b: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for PrivateDotCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            a: vec![Value::unknown(); self.a.len()],
+            b: vec![Value::unknown(); self.b.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let mut load = |values: &[Value<F>]| {
+            values
+                .iter()
+                .map(|value| chip.load_private(layouter.namespace(|| "load"), *value))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let a = load(&self.a)?;
+        let b = load(&self.b)?;
+
+        let dot = chip.dot(layouter.namespace(|| "a . b"), &a, &b)?;
+
+        chip.expose_public(layouter.namespace(|| "expose a . b"), dot, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::PrivateDotCircuit;
+
+    #[test]
+    fn private_dot() {
+        let k = 5;
+        let known = |values: &[u64]| values.iter().map(|v| Value::known(Fp::from(*v))).collect();
+
+        // 1 * 4 + 2 * 5 + 3 * 6 = 32
+        let circuit = PrivateDotCircuit {
+            a: known(&[1, 2, 3]),
+            b: known(&[4, 5, 6]),
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(32)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(33)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
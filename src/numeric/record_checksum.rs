@@ -0,0 +1,115 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::{
+    mod_reduce::ModReduceChip,
+    numeric::{FieldChip, FieldConfig, NumericInstructions, Number},
+};
+
+/// A record packs `FIELDS` fields of `FIELD_BITS` bits each, field 0 in the low
+/// bits. The last field is the checksum of the others.
+const FIELDS: usize = 4;
+const FIELD_BITS: usize = 8;
+const RECORD_BITS: usize = FIELDS * FIELD_BITS;
+/// The checksum is the sum of the data fields modulo this prime.
+const CHECKSUM_MODULUS: u64 = 251;
+
+/// Decomposes `record` into its fields, returned least significant first, and
+/// constrains the checksum field to equal the sum of the data fields mod
+/// `CHECKSUM_MODULUS`.
+fn assert_record_checksum<F: FieldExt>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    record: Number<F>,
+) -> Result<Vec<Number<F>>, Error> {
+    let bits = chip.decompose::<RECORD_BITS>(layouter.namespace(|| "decompose record"), record)?;
+    let fields = bits
+        .chunks(FIELD_BITS)
+        .map(|field| chip.compose(layouter.namespace(|| "compose field"), field))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (checksum, data) = fields.split_last().ok_or(Error::Synthesis)?;
+    let sum = chip.accumulate(layouter.namespace(|| "sum of data fields"), data)?;
+    let mod_chip = ModReduceChip::construct(chip.config().clone());
+    let expected = mod_chip.reduce::<FIELD_BITS>(
+        layouter.namespace(|| "sum mod m"),
+        sum,
+        F::from(CHECKSUM_MODULUS),
+    )?;
+    chip.constrain_equal(layouter.namespace(|| "checksum matches"), checksum, &expected)?;
+
+    Ok(fields)
+}
+
+/// Proves that the public record carries a valid checksum.
+#[derive(Default)]
+#[allow(dead_code)]
+struct RecordChecksumCircuit<F: FieldExt> {
+    record: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for RecordChecksumCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let record = chip.load_private(layouter.namespace(|| "load record"), self.record)?;
+        assert_record_checksum(&chip, layouter.namespace(|| "checksum"), record.clone())?;
+
+        chip.expose_public(layouter.namespace(|| "expose record"), record, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::{RecordChecksumCircuit, CHECKSUM_MODULUS, FIELD_BITS};
+
+    fn pack(fields: [u64; 4]) -> u64 {
+        fields.iter().rev().fold(0, |acc, field| acc << FIELD_BITS | field)
+    }
+
+    #[test]
+    fn record_checksum() {
+        let k = 8;
+        let run = |record: u64| {
+            let circuit = RecordChecksumCircuit {
+                record: Value::known(Fp::from(record)),
+            };
+            MockProver::run(k, &circuit, vec![vec![Fp::from(record)]]).unwrap()
+        };
+
+        // 12 + 200 + 77 = 289 = 251 + 38.
+        let data = [12, 200, 77];
+        let checksum = data.iter().sum::<u64>() % CHECKSUM_MODULUS;
+        assert_eq!(checksum, 38);
+        run(pack([12, 200, 77, checksum])).assert_satisfied();
+        run(pack([0, 0, 0, 0])).assert_satisfied();
+
+        // A corrupted data field no longer matches the checksum.
+        assert!(run(pack([12, 201, 77, checksum])).verify().is_err());
+        // The unreduced sum is not a valid checksum either.
+        assert!(run(pack([12, 200, 77, 289 % 256])).verify().is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error, Selector, TableColumn},
+    poly::Rotation,
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+use crate::range_check::example2::table::RangeTableConfig;
+
+/// The byte permutation `x -> x^3 mod 257`. Cubing permutes the integers mod
+/// 257 and maps 256 to itself, so it also permutes `[0, 256)`.
+fn substitute(byte: u64) -> u64 {
+    byte.pow(3) % 257
+}
+
+/// Applies a fixed nonlinear byte permutation through a lookup table.
+///
+/// The table maps every byte to its substitute. An input is looked up together
+/// with the witnessed output, which also range checks the input to a byte.
+/// Disabled lookup rows evaluate to `(0, 0)`, which is a table row since the
+/// permutation fixes zero.
+#[derive(Clone, Debug)]
+struct SboxConfig<F: FieldExt> {
+    field: FieldConfig<F>,
+    q_lookup: Selector,
+    table: RangeTableConfig<F, 256>,
+    output: TableColumn,
+}
+
+struct SboxChip<F: FieldExt> {
+    config: SboxConfig<F>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> SboxChip<F> {
+    fn construct(config: SboxConfig<F>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>, field: FieldConfig<F>) -> SboxConfig<F> {
+        let advice = field.advice;
+        let q_lookup = meta.complex_selector();
+        let table = RangeTableConfig::configure(meta);
+        let output = meta.lookup_table_column();
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_lookup);
+            let input = meta.query_advice(advice[0], Rotation::cur());
+            let output_value = meta.query_advice(advice[1], Rotation::cur());
+
+            vec![(q.clone() * input, table.value), (q * output_value, output)]
+        });
+
+        SboxConfig {
+            field,
+            q_lookup,
+            table,
+            output,
+        }
+    }
+
+    fn field_chip(&self) -> FieldChip<F> {
+        FieldChip::construct(self.config.field.clone())
+    }
+
+    fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.config.table.load_transformed(layouter, self.config.output, substitute)
+    }
+
+    /// Returns the substitute of the byte `input`.
+    fn sbox(&self, mut layouter: impl Layouter<F>, input: Number<F>) -> Result<Number<F>, Error> {
+        let advice = self.config.field.advice;
+
+        layouter.assign_region(
+            || "sbox",
+            |mut region| {
+                self.config.q_lookup.enable(&mut region, 0)?;
+
+                input.0.copy_advice(|| "input", &mut region, advice[0], 0)?;
+                let output = input
+                    .evaluate()
+                    .map(|input| Assigned::from(F::from(substitute(input.get_lower_128() as u64 & 0xFF))));
+                region
+                    .assign_advice(|| "output", advice[1], 0, || output)
+                    .map(Number)
+            },
+        )
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct SboxCircuit<F: FieldExt> {
+    input: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for SboxCircuit<F> {
+    type Config = SboxConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        let field = FieldChip::configure(meta, advice, instance, constant);
+        SboxChip::configure(meta, field)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = SboxChip::<F>::construct(config);
+        let field_chip = chip.field_chip();
+
+        chip.load(&mut layouter)?;
+
+        let input = field_chip.load_private(layouter.namespace(|| "load input"), self.input)?;
+        let output = chip.sbox(layouter.namespace(|| "sbox"), input)?;
+
+        field_chip.expose_public(layouter.namespace(|| "expose output"), output, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::{substitute, SboxCircuit};
+
+    #[test]
+    fn sbox() {
+        let k = 9;
+        let circuit = |input: u64| SboxCircuit {
+            input: Value::known(Fp::from(input)),
+        };
+
+        assert_eq!((0..256).map(substitute).collect::<HashSet<_>>().len(), 256);
+
+        // 2^3 = 8 and 10^3 = 1000 = 3 * 257 + 229.
+        for (input, output) in [(2, 8), (10, 229), (0, 0)] {
+            let prover = MockProver::run(k, &circuit(input), vec![vec![Fp::from(output)]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        let prover = MockProver::run(k, &circuit(10), vec![vec![Fp::from(230)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 256 is not a byte.
+        let prover = MockProver::run(k, &circuit(256), vec![vec![Fp::from(0)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Constrains `output` to be `input` permuted by `permutation`, a witnessed
+/// one-hot matrix: `output[i]` is the dot product of row `i` with `input`.
+///
+/// The matrix is doubly one-hot, so every entry is boolean and every row and
+/// column sums to one. Each input is then picked by exactly one output.
+fn assert_shuffle<F: FieldExt>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    input: &[Number<F>],
+    output: &[Number<F>],
+    permutation: &[Vec<Number<F>>],
+) -> Result<(), Error> {
+    let n = input.len();
+    if output.len() != n || permutation.len() != n || permutation.iter().any(|row| row.len() != n) {
+        return Err(Error::Synthesis);
+    }
+
+    for entry in permutation.iter().flatten() {
+        chip.decompose::<1>(layouter.namespace(|| "entry is boolean"), entry.clone())?;
+    }
+
+    let one = chip.load_constant(layouter.namespace(|| "one"), F::one())?;
+    for j in 0..n {
+        let row = &permutation[j];
+        let column = permutation.iter().map(|row| row[j].clone()).collect::<Vec<_>>();
+
+        let row_sum = chip.accumulate(layouter.namespace(|| "row sum"), row)?;
+        chip.constrain_equal(layouter.namespace(|| "row is one-hot"), &row_sum, &one)?;
+        let column_sum = chip.accumulate(layouter.namespace(|| "column sum"), &column)?;
+        chip.constrain_equal(layouter.namespace(|| "column is one-hot"), &column_sum, &one)?;
+
+        let picked = chip.dot(layouter.namespace(|| "row . input"), row, input)?;
+        chip.constrain_equal(layouter.namespace(|| "output == row . input"), &output[j], &picked)?;
+    }
+
+    Ok(())
+}
+
+/// Proves that the public `output` is a shuffle of the private `input`.
+#[derive(Default)]
+#[allow(dead_code)]
+struct ShuffleCircuit<F: FieldExt> {
+    input: Vec<Value<F>>,
+    output: Vec<Value<F>>,
+    permutation: Vec<Vec<Value<F>>>,
+}
+
+impl<F: FieldExt> Circuit<F> for ShuffleCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            input: vec![Value::unknown(); self.input.len()],
+            output: vec![Value::unknown(); self.output.len()],
+            permutation: self
+                .permutation
+                .iter()
+                .map(|row| vec![Value::unknown(); row.len()])
+                .collect(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let mut load = |values: &[Value<F>]| {
+            values
+                .iter()
+                .map(|value| chip.load_private(layouter.namespace(|| "load"), *value))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let input = load(&self.input)?;
+        let output = load(&self.output)?;
+        let permutation = self
+            .permutation
+            .iter()
+            .map(|row| load(row))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        assert_shuffle(&chip, layouter.namespace(|| "shuffle"), &input, &output, &permutation)?;
+
+        for (row, value) in output.into_iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| "expose output"), value, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::ShuffleCircuit;
+
+    #[test]
+    fn shuffle() {
+        let k = 8;
+        let known = |values: &[Fp]| values.iter().map(|v| Value::known(*v)).collect::<Vec<_>>();
+        let fp = |values: &[i64]| {
+            values
+                .iter()
+                .map(|v| if *v < 0 { -Fp::from(v.unsigned_abs()) } else { Fp::from(*v as u64) })
+                .collect::<Vec<_>>()
+        };
+
+        let circuit = |output: &[i64], permutation: &[[i64; 3]]| ShuffleCircuit {
+            input: known(&fp(&[10, 20, 30])),
+            output: known(&fp(output)),
+            permutation: permutation.iter().map(|row| known(&fp(row))).collect(),
+        };
+        let run = |output: &[i64], permutation: &[[i64; 3]]| {
+            MockProver::run(k, &circuit(output, permutation), vec![fp(output)]).unwrap()
+        };
+
+        let permutation = [[0, 0, 1], [1, 0, 0], [0, 1, 0]];
+        run(&[30, 10, 20], &permutation).assert_satisfied();
+
+        // The output does not follow the permutation.
+        assert!(run(&[30, 20, 10], &permutation).verify().is_err());
+
+        // Two outputs pick the same input, leaving a column empty.
+        let duplicate = [[0, 0, 1], [0, 0, 1], [0, 1, 0]];
+        assert!(run(&[30, 30, 20], &duplicate).verify().is_err());
+
+        // Rows and columns sum to one, but the entries are not boolean.
+        let weighted = [[2, -1, 0], [-1, 2, 0], [0, 0, 1]];
+        assert!(run(&[0, 30, 30], &weighted).verify().is_err());
+    }
+}
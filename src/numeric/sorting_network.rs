@@ -0,0 +1,127 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Batcher's odd-even merge sort on four wires: sort each half, merge the
+/// halves, then fix up the middle pair.
+const BATCHER_4: [(usize, usize); 5] = [(0, 1), (2, 3), (0, 2), (1, 3), (1, 2)];
+
+/// Sorts `BITS`-bit `xs` in ascending order with the fixed `network`.
+///
+/// Every comparator is a `sort_pair` compare-exchange, which keeps the pair it
+/// swaps, so the output is a permutation of the input by construction.
+fn sort<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    network: &[(usize, usize)],
+    xs: &[Number<F>],
+) -> Result<Vec<Number<F>>, Error> {
+    let mut wires = xs.to_vec();
+
+    for &(i, j) in network {
+        if i >= j || j >= wires.len() {
+            return Err(Error::Synthesis);
+        }
+
+        let (lo, hi) = chip.sort_pair::<BITS>(
+            layouter.namespace(|| "compare-exchange"),
+            wires[i].clone(),
+            wires[j].clone(),
+        )?;
+        wires[i] = lo;
+        wires[j] = hi;
+    }
+
+    Ok(wires)
+}
+
+/// Sorts four private values and exposes the sorted sequence.
+#[derive(Default)]
+#[allow(dead_code)]
+struct SortingNetworkCircuit<F: FieldExt> {
+    xs: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for SortingNetworkCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            xs: vec![Value::unknown(); self.xs.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let xs = self
+            .xs
+            .iter()
+            .map(|x| chip.load_private(layouter.namespace(|| "load x"), *x))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let sorted = sort::<F, 8>(&chip, layouter.namespace(|| "sort"), &BATCHER_4, &xs)?;
+
+        // The network sorts any input; check the output ordering regardless.
+        for pair in sorted.windows(2) {
+            chip.assert_less_equal::<8>(
+                layouter.namespace(|| "y_i <= y_i+1"),
+                pair[0].clone(),
+                pair[1].clone(),
+            )?;
+        }
+
+        for (row, y) in sorted.into_iter().enumerate() {
+            chip.expose_public(layouter.namespace(|| "expose y"), y, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::SortingNetworkCircuit;
+
+    #[test]
+    fn sorting_network() {
+        let k = 8;
+        let circuit = |xs: &[u64]| SortingNetworkCircuit {
+            xs: xs.iter().map(|x| Value::known(Fp::from(*x))).collect(),
+        };
+        let public = |ys: &[u64]| vec![ys.iter().map(|y| Fp::from(*y)).collect()];
+
+        for xs in [[3, 1, 4, 1], [9, 7, 5, 2], [0, 1, 2, 3], [200, 7, 200, 7]] {
+            let mut sorted = xs;
+            sorted.sort();
+            let prover = MockProver::run(k, &circuit(&xs), public(&sorted)).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // Sorted, but not a permutation of the input.
+        let prover = MockProver::run(k, &circuit(&[3, 1, 4, 1]), public(&[1, 1, 3, 5])).unwrap();
+        assert!(prover.verify().is_err());
+
+        // A permutation of the input, but not sorted.
+        let prover = MockProver::run(k, &circuit(&[3, 1, 4, 1]), public(&[1, 3, 1, 4])).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,135 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use super::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Returns the lower weighted median of `xs`: the first element at which the
+/// cumulative weight reaches half of the total.
+///
+/// `xs` is proven sorted, so with `flag_i = [2 * prefix_i < total]` the flags are
+/// a run of ones followed by zeros, and `x_0 + sum flag_i * (x_{i+1} - x_i)`
+/// telescopes to the median. Elements, weights and doubled prefix sums are
+/// range checked to `BITS` bits.
+fn weighted_median<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    xs: &[Number<F>],
+    weights: &[Number<F>],
+) -> Result<Number<F>, Error> {
+    if xs.is_empty() || xs.len() != weights.len() {
+        return Err(Error::Synthesis);
+    }
+
+    for pair in xs.windows(2) {
+        chip.assert_less_equal::<BITS>(layouter.namespace(|| "sorted"), pair[0].clone(), pair[1].clone())?;
+    }
+
+    let mut doubled_prefix = Vec::with_capacity(weights.len());
+    let mut prefix = chip.load_constant(layouter.namespace(|| "zero"), F::zero())?;
+    for weight in weights {
+        chip.decompose::<BITS>(layouter.namespace(|| "range check weight"), weight.clone())?;
+        prefix = chip.add(layouter.namespace(|| "prefix + weight"), prefix, weight.clone())?;
+
+        let doubled = chip.add(layouter.namespace(|| "2 * prefix"), prefix.clone(), prefix.clone())?;
+        chip.decompose::<BITS>(layouter.namespace(|| "range check 2 * prefix"), doubled.clone())?;
+        doubled_prefix.push(doubled);
+    }
+    let total = prefix;
+
+    let mut median = xs[0].clone();
+    for (i, doubled) in doubled_prefix.into_iter().take(xs.len() - 1).enumerate() {
+        let below_half =
+            chip.less_than::<BITS>(layouter.namespace(|| "2 * prefix < total"), doubled, total.clone())?;
+        let step =
+            chip.sub(layouter.namespace(|| "x_{i+1} - x_i"), xs[i + 1].clone(), xs[i].clone())?;
+        let step = chip.mul(layouter.namespace(|| "flag * step"), below_half, step)?;
+        median = chip.add(layouter.namespace(|| "median + step"), median, step)?;
+    }
+
+    Ok(median)
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct WeightedMedianCircuit<F: FieldExt> {
+    xs: Vec<Value<F>>,
+    weights: Vec<Value<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for WeightedMedianCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            xs: vec![Value::unknown(); self.xs.len()],
+            weights: vec![Value::unknown(); self.weights.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let mut load = |values: &[Value<F>]| {
+            values
+                .iter()
+                .map(|value| chip.load_private(layouter.namespace(|| "load"), *value))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let xs = load(&self.xs)?;
+        let weights = load(&self.weights)?;
+
+        let median =
+            weighted_median::<F, 8>(&chip, layouter.namespace(|| "weighted median"), &xs, &weights)?;
+
+        chip.expose_public(layouter.namespace(|| "expose median"), median, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    use super::WeightedMedianCircuit;
+
+    #[test]
+    fn weighted_median() {
+        let k = 8;
+        let circuit = |xs: &[u64], weights: &[u64]| WeightedMedianCircuit {
+            xs: xs.iter().map(|x| Value::known(Fp::from(*x))).collect(),
+            weights: weights.iter().map(|w| Value::known(Fp::from(*w))).collect(),
+        };
+
+        // Cumulative weights are [1, 2, 7, 8, 10]; half of 10 is first reached at 5.
+        let weighted = circuit(&[1, 3, 5, 7, 9], &[1, 1, 5, 1, 2]);
+
+        let prover = MockProver::run(k, &weighted, vec![vec![Fp::from(5)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &weighted, vec![vec![Fp::from(7)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Unsorted inputs are rejected even with the right median.
+        let unsorted = circuit(&[1, 3, 5, 9, 7], &[1, 1, 5, 1, 2]);
+        let prover = MockProver::run(k, &unsorted, vec![vec![Fp::from(5)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // Each element needs exactly one weight.
+        let mismatched = circuit(&[1, 3, 5], &[1, 1]);
+        assert!(MockProver::run(k, &mismatched, vec![vec![Fp::from(3)]]).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+use halo2_proofs::{
+    pasta::{EqAffine, Fp},
+    plonk::{create_proof, Circuit, Error, ProvingKey},
+    poly::commitment::Params,
+    transcript::{Blake2bWrite, Challenge255},
+};
+use rand_core::OsRng;
+
+/// Runs the real prover once with keys generated by the caller, returning the
+/// proof bytes and the wall-clock time spent in `create_proof`. Key generation
+/// is left to the caller so that it stays out of the measured time, and so that
+/// the same `params` and verifying key can check the proof afterwards.
+pub(crate) fn prove_and_measure<C: Circuit<Fp>>(
+    params: &Params<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instances: &[&[Fp]],
+) -> Result<(Vec<u8>, Duration), Error> {
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+
+    let start = Instant::now();
+    create_proof(params, pk, &[circuit], &[instances], OsRng, &mut transcript)?;
+    let elapsed = start.elapsed();
+
+    Ok((transcript.finalize(), elapsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::Value,
+        pasta::{EqAffine, Fp},
+        plonk::{keygen_pk, keygen_vk, verify_proof, SingleVerifier},
+        poly::commitment::Params,
+        transcript::{Blake2bRead, Challenge255},
+    };
+
+    use super::prove_and_measure;
+    use crate::numeric::numeric::MyCircuit;
+
+    #[test]
+    fn prove_and_measure_numeric() {
+        let k = 4;
+        let constant = Fp::from(7);
+        let a = Fp::from(2);
+        let b = Fp::from(3);
+        let ab = a * b;
+        let c = constant * ab.square();
+
+        let circuit = MyCircuit {
+            constant,
+            a: Value::known(a),
+            b: Value::known(b),
+        };
+
+        let params: Params<EqAffine> = Params::new(k);
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+
+        let public_input = [ab, ab.square(), c];
+        let (proof, _elapsed) = prove_and_measure(&params, &pk, circuit, &[&public_input]).unwrap();
+        assert!(!proof.is_empty());
+
+        let verify = |instance: &[Fp]| {
+            let strategy = SingleVerifier::new(&params);
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+            verify_proof(&params, pk.get_vk(), strategy, &[&[instance]], &mut transcript)
+        };
+
+        assert!(verify(&public_input).is_ok());
+        assert!(verify(&[ab, ab.square(), c + Fp::one()]).is_err());
+    }
+}
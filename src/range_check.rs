@@ -1,2 +1,5 @@
-mod example1;
-mod example2;
\ No newline at end of file
+pub(crate) mod example1;
+pub(crate) mod example2;
+mod example3;
+mod base_n;
+mod comparison;
@@ -0,0 +1,304 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value, floor_planner::V1},
+    plonk::{Advice, Assigned, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+
+use super::example2::table::RangeTableConfig;
+
+/// Largest supported base: digits are range checked with lookups into a table
+/// of `[0, MAX_BASE)`.
+const MAX_BASE: usize = 256;
+
+/// A single base-N digit produced by the BaseNConfig.
+#[derive(Clone, Debug)]
+struct Digit<F: FieldExt>(AssignedCell<Assigned<F>, F>);
+
+/// The digits of a value, least significant first, and the cell holding it.
+type Encoding<F> = (Vec<Digit<F>>, AssignedCell<Assigned<F>, F>);
+
+/// Decomposes a value into `DIGITS` base-N digits.
+///
+/// Digits are consumed most significant first: each row multiplies the running
+/// sum by the base, held in the `radix` fixed column, and adds the digit. A digit
+/// is shown to be `< base` by looking up both `digit` and `base - 1 - digit` in
+/// the range table.
+#[derive(Clone, Debug)]
+struct BaseNConfig<F: FieldExt, const DIGITS: usize> {
+    digit: Column<Advice>,
+    acc: Column<Advice>,
+    radix: Column<Fixed>,
+    instance: Column<Instance>,
+    q_digit: Selector,
+    table: RangeTableConfig<F, MAX_BASE>,
+}
+
+impl<F: FieldExt, const DIGITS: usize> BaseNConfig<F, DIGITS> {
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        let digit = meta.advice_column();
+        let acc = meta.advice_column();
+        let radix = meta.fixed_column();
+        let constant = meta.fixed_column();
+        let instance = meta.instance_column();
+        let q_digit = meta.complex_selector();
+        let table = RangeTableConfig::configure(meta);
+
+        meta.enable_equality(digit);
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+        meta.enable_constant(constant);
+
+        meta.create_gate("base-n recomposition", |meta| {
+            let q = meta.query_selector(q_digit);
+            let digit = meta.query_advice(digit, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let radix = meta.query_fixed(radix, Rotation::cur());
+
+            vec![q * (acc * radix + digit - acc_next)]
+        });
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_digit);
+            let digit = meta.query_advice(digit, Rotation::cur());
+
+            vec![(q * digit, table.value)]
+        });
+
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_digit);
+            let digit = meta.query_advice(digit, Rotation::cur());
+            let radix = meta.query_fixed(radix, Rotation::cur());
+
+            vec![(q * (radix - Expression::Constant(F::one()) - digit), table.value)]
+        });
+
+        Self {
+            digit,
+            acc,
+            radix,
+            instance,
+            q_digit,
+            table,
+        }
+    }
+
+    /// Assigns `value` and its base-`base` digits, least significant first.
+    fn encode_base_n(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+        base: u64,
+    ) -> Result<Vec<Digit<F>>, Error> {
+        self.encode(layouter, value, base).map(|(digits, _)| digits)
+    }
+
+    /// Returns the base-`to_base` digits of `value`, least significant first,
+    /// after proving that its base-`from_base` digits encode the same value.
+    fn convert_base(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+        from_base: u64,
+        to_base: u64,
+    ) -> Result<Vec<Digit<F>>, Error> {
+        let (_, from_value) = self.encode(layouter.namespace(|| "encode from_base"), value, from_base)?;
+        let (digits, to_value) = self.encode(layouter.namespace(|| "encode to_base"), value, to_base)?;
+
+        layouter.assign_region(
+            || "shared value",
+            |mut region| {
+                let from_value = from_value.copy_advice(|| "value", &mut region, self.acc, 0)?;
+                region.constrain_equal(from_value.cell(), to_value.cell())
+            },
+        )?;
+
+        Ok(digits)
+    }
+
+    /// Assigns the digits of `value` as in [`Self::encode_base_n`], and also
+    /// returns the cell holding `value`, the final running sum.
+    fn encode(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+        base: u64,
+    ) -> Result<Encoding<F>, Error> {
+        assert!(2 <= base && base <= MAX_BASE as u64);
+
+        layouter.assign_region(
+            || "encode base n",
+            |mut region| {
+                let value_int = value.map(|v| v.evaluate().get_lower_128());
+                let radix = Assigned::from(F::from(base));
+
+                let mut digits = Vec::with_capacity(DIGITS);
+                let mut acc = region
+                    .assign_advice_from_constant(|| "acc", self.acc, 0, Assigned::from(F::zero()))?;
+
+                for row in 0..DIGITS {
+                    self.q_digit.enable(&mut region, row)?;
+                    region.assign_fixed(|| "radix", self.radix, row, || Value::known(radix))?;
+
+                    let place = (base as u128).pow((DIGITS - 1 - row) as u32);
+                    let digit_value =
+                        value_int.map(|v| Assigned::from(F::from_u128(v / place % base as u128)));
+                    let digit = region.assign_advice(|| "digit", self.digit, row, || digit_value)?;
+
+                    // The final running sum is the encoded value itself.
+                    let acc_value = if row + 1 == DIGITS {
+                        value
+                    } else {
+                        acc.value().map(|acc| *acc * radix) + digit.value()
+                    };
+                    acc = region.assign_advice(|| "acc", self.acc, row + 1, || acc_value)?;
+
+                    digits.push(Digit(digit));
+                }
+
+                digits.reverse();
+                Ok((digits, acc))
+            },
+        )
+    }
+
+    fn expose_public(
+        &self,
+        mut layouter: impl Layouter<F>,
+        digit: Digit<F>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(digit.0.cell(), self.instance, row)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct MyCircuit<F: FieldExt, const DIGITS: usize> {
+    value: Value<Assigned<F>>,
+    base: u64,
+}
+
+impl<F: FieldExt, const DIGITS: usize> Circuit<F> for MyCircuit<F, DIGITS> {
+    type Config = BaseNConfig<F, DIGITS>;
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: Value::unknown(),
+            base: self.base,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BaseNConfig::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+
+        let digits = config.encode_base_n(layouter.namespace(|| "encode"), self.value, self.base)?;
+        for (row, digit) in digits.into_iter().enumerate() {
+            config.expose_public(layouter.namespace(|| "expose digit"), digit, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct ConvertCircuit<F: FieldExt, const DIGITS: usize> {
+    value: Value<Assigned<F>>,
+    from_base: u64,
+    to_base: u64,
+}
+
+impl<F: FieldExt, const DIGITS: usize> Circuit<F> for ConvertCircuit<F, DIGITS> {
+    type Config = BaseNConfig<F, DIGITS>;
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: Value::unknown(),
+            ..*self
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        BaseNConfig::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        config.table.load(&mut layouter)?;
+
+        let digits = config.convert_base(
+            layouter.namespace(|| "convert"),
+            self.value,
+            self.from_base,
+            self.to_base,
+        )?;
+        for (row, digit) in digits.into_iter().enumerate() {
+            config.expose_public(layouter.namespace(|| "expose digit"), digit, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    #[test]
+    fn test_encode_base_n() {
+        let k = 9;
+
+        let circuit = |value: u64, base: u64| MyCircuit::<Fp, 2> {
+            value: Value::known(Fp::from(value).into()),
+            base,
+        };
+        let digits = |digits: [u64; 2]| digits.map(Fp::from).to_vec();
+
+        let prover = MockProver::run(k, &circuit(255, 16), vec![digits([15, 15])]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(42, 10), vec![digits([2, 4])]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(255, 16), vec![digits([14, 15])]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 256 does not fit in two base-16 digits.
+        let prover = MockProver::run(k, &circuit(256, 16), vec![digits([0, 0])]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_convert_base() {
+        let k = 9;
+
+        let circuit = |value: u64| ConvertCircuit::<Fp, 3> {
+            value: Value::known(Fp::from(value).into()),
+            from_base: 10,
+            to_base: 16,
+        };
+        let digits = |digits: [u64; 3]| digits.map(Fp::from).to_vec();
+
+        // 255 is [5, 5, 2] in decimal and [15, 15] in hex, padded to three digits.
+        let prover = MockProver::run(k, &circuit(255), vec![digits([15, 15, 0])]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit(255), vec![digits([14, 15, 0])]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 1000 does not fit in three decimal digits.
+        let prover = MockProver::run(k, &circuit(1000), vec![digits([8, 14, 3])]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::numeric::numeric::{FieldChip, FieldConfig, NumericInstructions, Number};
+
+/// Constrains `lo <= value < hi` with two comparisons instead of a lookup table.
+///
+/// Each comparison range checks a difference to `BITS` bits, so the cost grows
+/// with `BITS` rather than with the size of the range. `hi - lo` must fit in
+/// `BITS` bits.
+fn assert_in_range_cmp<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    value: Number<F>,
+    lo: F,
+    hi: F,
+) -> Result<(), Error> {
+    let lo = chip.load_constant(layouter.namespace(|| "lo"), lo)?;
+    let hi = chip.load_constant(layouter.namespace(|| "hi"), hi)?;
+
+    chip.assert_less_equal::<BITS>(layouter.namespace(|| "lo <= value"), lo, value.clone())?;
+    chip.assert_less_than::<BITS>(layouter.namespace(|| "value < hi"), value, hi)
+}
+
+/// Constrains `value` to lie on the circle `[0, modulus)` within the interval
+/// from `lo` up to, but excluding, `hi`.
+///
+/// If `lo > hi` the interval wraps past the modulus, as `[350, 10)` does on a
+/// 0..360 circle, and `value` must avoid the gap `[hi, lo)` instead: the gap
+/// holds exactly the values that are below `lo` but not below `hi`.
+fn assert_in_circular_range<F: FieldExt, const BITS: usize>(
+    chip: &FieldChip<F>,
+    mut layouter: impl Layouter<F>,
+    value: Number<F>,
+    lo: F,
+    hi: F,
+    modulus: F,
+) -> Result<(), Error> {
+    let modulus = chip.load_constant(layouter.namespace(|| "modulus"), modulus)?;
+    chip.assert_less_than::<BITS>(layouter.namespace(|| "value < modulus"), value.clone(), modulus)?;
+
+    if lo.get_lower_128() <= hi.get_lower_128() {
+        return assert_in_range_cmp::<F, BITS>(chip, layouter, value, lo, hi);
+    }
+
+    let lo = chip.load_constant(layouter.namespace(|| "lo"), lo)?;
+    let hi = chip.load_constant(layouter.namespace(|| "hi"), hi)?;
+
+    let below_lo = chip.less_than::<BITS>(layouter.namespace(|| "value < lo"), value.clone(), lo)?;
+    let below_hi = chip.less_than::<BITS>(layouter.namespace(|| "value < hi"), value, hi)?;
+    chip.constrain_equal(layouter.namespace(|| "value not in [hi, lo)"), &below_lo, &below_hi)
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct MyCircuit<F: FieldExt> {
+    value: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        let instance = meta.instance_column();
+        let constant = meta.fixed_column();
+        FieldChip::configure(meta, advice, instance, constant)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+        assert_in_range_cmp::<F, 8>(
+            &chip,
+            layouter.namespace(|| "100 <= value < 200"),
+            value,
+            F::from(100),
+            F::from(200),
+        )
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct CircularCircuit<F: FieldExt> {
+    value: Value<F>,
+    lo: u64,
+    hi: u64,
+}
+
+impl<F: FieldExt> Circuit<F> for CircularCircuit<F> {
+    type Config = FieldConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            value: Value::unknown(),
+            ..*self
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MyCircuit::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        let chip = FieldChip::<F>::construct(config);
+
+        let value = chip.load_private(layouter.namespace(|| "load value"), self.value)?;
+        assert_in_circular_range::<F, 9>(
+            &chip,
+            layouter.namespace(|| "value in [lo, hi) mod 360"),
+            value,
+            F::from(self.lo),
+            F::from(self.hi),
+            F::from(360),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    #[test]
+    fn test_assert_in_range_cmp() {
+        let k = 6;
+        let circuit = |value: u64| MyCircuit {
+            value: Value::known(Fp::from(value)),
+        };
+
+        for value in [150, 100, 199] {
+            let prover = MockProver::run(k, &circuit(value), vec![vec![]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        for value in [99, 200, 250] {
+            let prover = MockProver::run(k, &circuit(value), vec![vec![]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+
+    #[test]
+    fn test_assert_in_circular_range() {
+        let k = 7;
+        let circuit = |value: u64, lo: u64, hi: u64| CircularCircuit {
+            value: Value::known(Fp::from(value)),
+            lo,
+            hi,
+        };
+
+        let inside = [
+            (90, 90, 180),
+            (179, 90, 180),
+            (350, 350, 10),
+            (359, 350, 10),
+            (0, 350, 10),
+            (9, 350, 10),
+        ];
+        for (value, lo, hi) in inside {
+            let prover = MockProver::run(k, &circuit(value, lo, hi), vec![vec![]]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        // 360 is in the wrapping interval modulo 360, but is not on the circle.
+        let outside = [
+            (89, 90, 180),
+            (180, 90, 180),
+            (10, 350, 10),
+            (180, 350, 10),
+            (349, 350, 10),
+            (360, 350, 10),
+        ];
+        for (value, lo, hi) in outside {
+            let prover = MockProver::run(k, &circuit(value, lo, hi), vec![vec![]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}
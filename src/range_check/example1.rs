@@ -8,17 +8,17 @@ use halo2_proofs::{
 };
 
 #[derive(Clone, Debug)]
-struct RangeConstrained<F: FieldExt, const RANGE: usize>(AssignedCell<Assigned<F>, F>);
+pub(crate) struct RangeConstrained<F: FieldExt, const RANGE: usize>(pub(crate) AssignedCell<Assigned<F>, F>);
 
 #[derive(Clone, Debug)]
-struct RangeCheckConfig<F: FieldExt, const RANGE: usize> {
+pub(crate) struct RangeCheckConfig<F: FieldExt, const RANGE: usize> {
     value: Column<Advice>,
     q_range_check: Selector,
     _marker: PhantomData<F>,
 }
 
 impl<F: FieldExt, const RANGE: usize> RangeCheckConfig<F, RANGE> {
-    fn configure(meta: &mut ConstraintSystem<F>, value: Column<Advice>) -> Self {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>, value: Column<Advice>) -> Self {
         let q_range_check = meta.selector();
 
         meta.create_gate("range check", |meta| {
@@ -41,7 +41,7 @@ impl<F: FieldExt, const RANGE: usize> RangeCheckConfig<F, RANGE> {
         }
     }
 
-    fn assign(
+    pub(crate) fn assign(
         &self,
         mut layouter: impl Layouter<F>,
         value: Value<Assigned<F>>,
@@ -63,6 +63,7 @@ impl<F: FieldExt, const RANGE: usize> RangeCheckConfig<F, RANGE> {
 }
 
 #[derive(Default)]
+#[allow(dead_code)]
 struct MyCircuit<F: FieldExt, const RANGE: usize> {
     value: Value<Assigned<F>>,
 }
@@ -1,4 +1,4 @@
-mod table;
+pub(crate) mod table;
 use std::vec;
 
 use table::*;
@@ -6,7 +6,9 @@ use table::*;
 use halo2_proofs::{
     arithmetic::FieldExt,
     circuit::{AssignedCell, Layouter, Value, floor_planner::V1},
-    plonk::{Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Selector, Circuit},
+    plonk::{
+        Advice, Assigned, Column, ConstraintSystem, Constraints, Error, Expression, Instance, Selector, Circuit,
+    },
     poly::Rotation,
 };
 
@@ -20,17 +22,25 @@ struct RangeCheckConfig<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usi
     q_range_check: Selector,
     q_lookup: Selector,
     value: Column<Advice>,
+    instance: Column<Instance>,
     table: RangeTableConfig<F, LOOKUP_RANGE>,
 }
 
 impl<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize>
     RangeCheckConfig<F, RANGE, LOOKUP_RANGE>      
 {
-    pub fn configure(meta: &mut ConstraintSystem<F>, value: Column<Advice>) -> Self {
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        value: Column<Advice>,
+        instance: Column<Instance>,
+    ) -> Self {
         let q_range_check = meta.selector();
         let q_lookup = meta.complex_selector();
         let table = RangeTableConfig::configure(meta);
 
+        meta.enable_equality(value);
+        meta.enable_equality(instance);
+
         meta.create_gate("range check", |meta| {
             let q = meta.query_selector(q_range_check);
             let value = meta.query_advice(value, Rotation::cur());
@@ -55,6 +65,7 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize>
             q_range_check,
             q_lookup,
             value,
+            instance,
             table,
         }
     }
@@ -94,9 +105,20 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize>
             }, 
         )
     }
+
+    /// Constrains a range-checked value to equal the public input at `row`.
+    fn expose<const R: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &RangeConstrained<F, R>,
+        row: usize,
+    ) -> Result<(), Error> {
+        layouter.constrain_instance(value.0.cell(), self.instance, row)
+    }
 }
 
 #[derive(Default)]
+#[allow(dead_code)]
 struct MyCircuit<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> {
     value: Value<Assigned<F>>,
     lookup_value: Value<Assigned<F>>,
@@ -114,16 +136,18 @@ impl<F: FieldExt, const RANGE: usize, const LOOKUP_RANGE: usize> Circuit<F>
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
         let value = meta.advice_column();
-        RangeCheckConfig::configure(meta, value)
+        let instance = meta.instance_column();
+        RangeCheckConfig::configure(meta, value, instance)
     }
 
     fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
         config.table.load(&mut layouter)?;
 
         config.assign_simple(layouter.namespace(|| "Assign for simple"), self.value)?;
-        config.assign_lookup(layouter.namespace(|| "Assign for lookup"), self.lookup_value)?;
+        let lookup_value =
+            config.assign_lookup(layouter.namespace(|| "Assign for lookup"), self.lookup_value)?;
 
-        Ok(())
+        config.expose(layouter.namespace(|| "Expose lookup value"), &lookup_value, 0)
     }
 }
 
@@ -149,9 +173,25 @@ mod tests {
                     lookup_value: Value::known(Fp::from(j as u64).into()),
                 };
 
-                let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+                let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(j as u64)]]).unwrap();
                 prover.assert_satisfied();
             }
         }
     }
+
+    #[test]
+    fn test_range_check_2_expose() {
+        let k = 9;
+
+        let circuit = MyCircuit::<Fp, 8, 256> {
+            value: Value::known(Fp::from(3).into()),
+            lookup_value: Value::known(Fp::from(200).into()),
+        };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(200)]]).unwrap();
+        prover.assert_satisfied();
+
+        let prover = MockProver::run(k, &circuit, vec![vec![Fp::from(201)]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
 }
\ No newline at end of file
@@ -7,13 +7,13 @@ use halo2_proofs::{
 };
 
 #[derive(Clone, Debug)]
-pub(super) struct RangeTableConfig<F: FieldExt, const RANGE: usize> {
-    pub(super) value: TableColumn,
+pub(crate) struct RangeTableConfig<F: FieldExt, const RANGE: usize> {
+    pub(crate) value: TableColumn,
     _marker: PhantomData<F>,
 }
 
 impl<F: FieldExt, const RANGE: usize> RangeTableConfig<F, RANGE> {
-    pub(super) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+    pub(crate) fn configure(meta: &mut ConstraintSystem<F>) -> Self {
         let value = meta.lookup_table_column();
 
         Self {
@@ -22,25 +22,42 @@ impl<F: FieldExt, const RANGE: usize> RangeTableConfig<F, RANGE> {
         }
     }
 
-    pub(super) fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+    pub(crate) fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
         layouter.assign_table(
             || "load range check table", 
             |mut table| {
-                let mut offset = 0;
-
-                for value in 0..RANGE {
+                for (offset, value) in (0..RANGE).enumerate() {
                     table.assign_cell(
                         || "num bits", 
                         self.value, 
                         offset, 
                         || Value::known(F::from(value as u64)),
                     )?;
+                }
+
+                Ok(())
+            },
+        )
+    }
 
-                    offset += 1;
+    /// Loads the table together with `f(value)` in `output`, so that lookups of
+    /// `(value, output)` pairs prove `output == f(value)` for `value` in range.
+    pub(crate) fn load_transformed(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        output: TableColumn,
+        f: impl Fn(u64) -> u64,
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "load transformed range check table",
+            |mut table| {
+                for (offset, value) in (0..RANGE as u64).enumerate() {
+                    table.assign_cell(|| "value", self.value, offset, || Value::known(F::from(value)))?;
+                    table.assign_cell(|| "f(value)", output, offset, || Value::known(F::from(f(value))))?;
                 }
 
                 Ok(())
             },
         )
     }
-}
\ No newline at end of file
+}
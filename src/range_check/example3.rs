@@ -0,0 +1,98 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, Value, floor_planner::V1},
+    plonk::{Assigned, Circuit, ConstraintSystem, Error},
+};
+
+use super::example1::{RangeCheckConfig, RangeConstrained};
+
+/// Several independent range checks sharing one advice column. Each range has
+/// its own gate and selector, so a value only pays for the range it is assigned to.
+#[derive(Clone, Debug)]
+struct MultiRangeCheckConfig<F: FieldExt> {
+    range8: RangeCheckConfig<F, 8>,
+    range16: RangeCheckConfig<F, 16>,
+}
+
+impl<F: FieldExt> MultiRangeCheckConfig<F> {
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        let value = meta.advice_column();
+
+        Self {
+            range8: RangeCheckConfig::configure(meta, value),
+            range16: RangeCheckConfig::configure(meta, value),
+        }
+    }
+
+    fn assign_range8(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+    ) -> Result<RangeConstrained<F, 8>, Error> {
+        self.range8.assign(layouter, value)
+    }
+
+    fn assign_range16(
+        &self,
+        layouter: impl Layouter<F>,
+        value: Value<Assigned<F>>,
+    ) -> Result<RangeConstrained<F, 16>, Error> {
+        self.range16.assign(layouter, value)
+    }
+}
+
+#[derive(Default)]
+#[allow(dead_code)]
+struct MyCircuit<F: FieldExt> {
+    value8: Value<Assigned<F>>,
+    value16: Value<Assigned<F>>,
+}
+
+impl<F: FieldExt> Circuit<F> for MyCircuit<F> {
+    type Config = MultiRangeCheckConfig<F>;
+    type FloorPlanner = V1;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        MultiRangeCheckConfig::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, mut layouter: impl Layouter<F>) -> Result<(), Error> {
+        config.assign_range8(layouter.namespace(|| "Assign range 8"), self.value8)?;
+        config.assign_range16(layouter.namespace(|| "Assign range 16"), self.value16)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        dev::MockProver,
+        pasta::Fp,
+    };
+
+    #[test]
+    fn test_range_check_3() {
+        let k = 4;
+
+        let circuit = |value8: u64, value16: u64| MyCircuit::<Fp> {
+            value8: Value::known(Fp::from(value8).into()),
+            value16: Value::known(Fp::from(value16).into()),
+        };
+
+        for (value8, value16) in [(0, 0), (5, 12), (7, 15)] {
+            let prover = MockProver::run(k, &circuit(value8, value16), vec![]).unwrap();
+            prover.assert_satisfied();
+        }
+
+        for (value8, value16) in [(8, 12), (12, 5), (5, 16)] {
+            let prover = MockProver::run(k, &circuit(value8, value16), vec![]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+    }
+}